@@ -1,14 +1,20 @@
-use std::{env, path::PathBuf};
+use std::env;
+#[cfg(feature = "bindgen")]
+use std::path::PathBuf;
 
+#[cfg(feature = "bindgen")]
 use bindgen::callbacks::{IntKind, ParseCallbacks};
+#[cfg(feature = "bindgen")]
 use regex::Regex;
 
 /// This code has been shamelessly adapted from
 /// https://rust-lang.github.io/rust-bindgen/tutorial-3.html
 
 // well okay, I may have made some slight modifications
+#[cfg(feature = "bindgen")]
 #[derive(Debug)]
 struct SetMacroTypeFromRegex(Regex, IntKind);
+#[cfg(feature = "bindgen")]
 impl ParseCallbacks for SetMacroTypeFromRegex {
     fn int_macro(&self, _name: &str, _value: i64) -> Option<bindgen::callbacks::IntKind> {
         Some(self.1).filter(|_| self.0.is_match(_name))
@@ -17,8 +23,20 @@ impl ParseCallbacks for SetMacroTypeFromRegex {
 
 fn main() {
     // println!("cargo:rustc-link-search")
-    println!("cargo:rustc-link-lib=sensors");
+    // With only `dlopen`, libsensors is loaded at runtime and must not be a link-time dependency.
+    if env::var_os("CARGO_FEATURE_STATIC").is_some() {
+        println!("cargo:rustc-link-lib=static=sensors");
+    } else if env::var_os("CARGO_FEATURE_LINK").is_some() {
+        println!("cargo:rustc-link-lib=sensors");
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+
+    #[cfg(feature = "bindgen")]
+    generate_bindings();
+}
 
+#[cfg(feature = "bindgen")]
+fn generate_bindings() {
     let bindings = bindgen::Builder::default()
         .header("wrapper.h")
         .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()))
@@ -0,0 +1,124 @@
+use std::{env, ffi::OsString, path::PathBuf, result::Result as StdResult};
+
+use crate::{ConfigSource, LibSensors, LoadingError};
+
+/// The environment variable that overrides which library file is loaded.
+///
+/// If set, its value is the only path that is tried, regardless of
+/// [`LibSensorsBuilder::library_path`] and [`LibSensorsBuilder::soname_candidates`].
+pub const LIBRARY_ENV_VAR: &str = "LIBSENSORS_RS_LIBRARY";
+
+/// The names tried if no others are given.
+pub const DEFAULT_SONAMES: &[&str] = &["libsensors.so.5"];
+
+/// Configures how libsensors is found and initialised (see [`LibSensors::builder`]).
+///
+/// With the `link` feature, libsensors is linked into the binary, so the library options have no effect.
+#[derive(Debug, Clone, Default)]
+pub struct LibSensorsBuilder {
+    config: ConfigSource,
+    library_path: Option<PathBuf>,
+    soname_candidates: Option<Vec<OsString>>,
+}
+impl LibSensorsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The configuration passed to libsensors. Defaults to [`ConfigSource::Default`].
+    pub fn config(mut self, config: impl Into<ConfigSource>) -> Self {
+        self.config = config.into();
+        self
+    }
+
+    /// Where to look for the library.
+    ///
+    /// If this is a directory, every soname candidate is looked up in it.
+    /// Otherwise it is the library file itself and the candidates are ignored.
+    /// If unset, the candidates are looked up in the default search path of the dynamic linker.
+    pub fn library_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.library_path = Some(path.into());
+        self
+    }
+
+    /// The file names to try, in order. Defaults to [`DEFAULT_SONAMES`].
+    pub fn soname_candidates<S: Into<OsString>>(mut self, names: impl IntoIterator<Item = S>) -> Self {
+        self.soname_candidates = Some(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// The paths that will be passed to the dynamic linker, in order.
+    ///
+    /// This includes the override from [`LIBRARY_ENV_VAR`].
+    pub fn candidates(&self) -> Vec<PathBuf> {
+        self.candidates_with(env::var_os(LIBRARY_ENV_VAR))
+    }
+
+    /// [`Self::candidates`] with the value of [`LIBRARY_ENV_VAR`] passed in.
+    fn candidates_with(&self, env_override: Option<OsString>) -> Vec<PathBuf> {
+        if let Some(path) = env_override.filter(|path| !path.is_empty()) {
+            return vec![PathBuf::from(path)]
+        }
+        if let Some(path) = self.library_path.as_ref().filter(|path| !path.is_dir()) {
+            return vec![path.clone()]
+        }
+        let names = self.soname_candidates.clone()
+            .unwrap_or_else(|| DEFAULT_SONAMES.iter().map(OsString::from).collect());
+        names.into_iter()
+            .map(|name| match &self.library_path {
+                Some(dir) => dir.join(name),
+                None => PathBuf::from(name),
+            })
+            .collect()
+    }
+
+    /// Loads and initialises libsensors.
+    ///
+    /// The same restrictions as for [`LibSensors::init`] apply.
+    /// Returns [`LoadingError::LibraryNotFound`] if none of the [`Self::candidates`] could be opened.
+    pub fn init(self) -> StdResult<LibSensors, LoadingError> {
+        LibSensors::init_with_builder(&self)
+    }
+
+    pub(crate) fn config_source(&self) -> &ConfigSource {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_candidates() {
+        let candidates = LibSensorsBuilder::new().candidates_with(None);
+        assert_eq!(candidates, DEFAULT_SONAMES.iter().map(PathBuf::from).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn candidates_in_directory() {
+        let dir = env::temp_dir();
+        let builder = LibSensorsBuilder::new()
+            .library_path(&dir)
+            .soname_candidates(["libsensors.so.5", "libsensors.so"]);
+        assert_eq!(builder.candidates_with(None), [dir.join("libsensors.so.5"), dir.join("libsensors.so")]);
+    }
+
+    #[test]
+    fn library_file_ignores_candidates() {
+        let builder = LibSensorsBuilder::new()
+            .library_path("/nonexistent/libsensors.so.5")
+            .soname_candidates(["libsensors.so"]);
+        assert_eq!(builder.candidates_with(None), [PathBuf::from("/nonexistent/libsensors.so.5")]);
+    }
+
+    #[test]
+    fn env_var_overrides_everything() {
+        let builder = LibSensorsBuilder::new()
+            .library_path(env::temp_dir())
+            .soname_candidates(["libsensors.so"]);
+        assert_eq!(builder.candidates_with(Some("/opt/libsensors.so".into())), [PathBuf::from("/opt/libsensors.so")]);
+        // An empty value counts as unset.
+        assert_eq!(builder.candidates_with(Some("".into())), [env::temp_dir().join("libsensors.so")]);
+    }
+}
@@ -0,0 +1,282 @@
+/* Bindings for the libsensors 3.x ABI (SENSORS_API_VERSION 0x5xx).
+ *
+ * These mirror what bindgen generates from `wrapper.h` with the options used in `build.rs`
+ * (constified enum modules for the feature/subfeature types, bus macros as `c_short`),
+ * restricted to the items this crate actually uses.
+ * Regenerate with the `bindgen` feature and compare when moving to a new ABI.
+ */
+
+pub const SENSORS_API_VERSION: u32 = 1280;
+pub const SENSORS_CHIP_NAME_ADDR_ANY: i32 = -1;
+pub const SENSORS_BUS_TYPE_ANY: ::std::os::raw::c_short = -1;
+pub const SENSORS_BUS_TYPE_I2C: ::std::os::raw::c_short = 0;
+pub const SENSORS_BUS_TYPE_ISA: ::std::os::raw::c_short = 1;
+pub const SENSORS_BUS_TYPE_PCI: ::std::os::raw::c_short = 2;
+pub const SENSORS_BUS_TYPE_SPI: ::std::os::raw::c_short = 3;
+pub const SENSORS_BUS_TYPE_VIRTUAL: ::std::os::raw::c_short = 4;
+pub const SENSORS_BUS_TYPE_ACPI: ::std::os::raw::c_short = 5;
+pub const SENSORS_BUS_TYPE_HID: ::std::os::raw::c_short = 6;
+pub const SENSORS_BUS_TYPE_MDIO: ::std::os::raw::c_short = 7;
+pub const SENSORS_BUS_TYPE_SCSI: ::std::os::raw::c_short = 8;
+pub const SENSORS_BUS_NR_ANY: ::std::os::raw::c_short = -1;
+pub const SENSORS_BUS_NR_IGNORE: ::std::os::raw::c_short = -2;
+pub const SENSORS_MODE_R: u32 = 1;
+pub const SENSORS_MODE_W: u32 = 2;
+pub const SENSORS_COMPUTE_MAPPING: u32 = 4;
+pub const SENSORS_ERR_WILDCARDS: u32 = 1;
+pub const SENSORS_ERR_NO_ENTRY: u32 = 2;
+pub const SENSORS_ERR_ACCESS_R: u32 = 3;
+pub const SENSORS_ERR_KERNEL: u32 = 4;
+pub const SENSORS_ERR_DIV_ZERO: u32 = 5;
+pub const SENSORS_ERR_CHIP_NAME: u32 = 6;
+pub const SENSORS_ERR_BUS_NAME: u32 = 7;
+pub const SENSORS_ERR_PARSE: u32 = 8;
+pub const SENSORS_ERR_ACCESS_W: u32 = 9;
+pub const SENSORS_ERR_IO: u32 = 10;
+pub const SENSORS_ERR_RECURSION: u32 = 11;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _IO_FILE {
+    _unused: [u8; 0],
+}
+pub type FILE = _IO_FILE;
+
+unsafe extern "C" {
+    pub static mut libsensors_version: *const ::std::os::raw::c_char;
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct sensors_bus_id {
+    pub type_: ::std::os::raw::c_short,
+    pub nr: ::std::os::raw::c_short,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct sensors_chip_name {
+    pub prefix: *mut ::std::os::raw::c_char,
+    pub bus: sensors_bus_id,
+    pub addr: ::std::os::raw::c_int,
+    pub path: *mut ::std::os::raw::c_char,
+}
+unsafe extern "C" {
+    pub fn sensors_init(input: *mut FILE) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    pub fn sensors_cleanup();
+}
+unsafe extern "C" {
+    pub fn sensors_parse_chip_name(
+        orig_name: *const ::std::os::raw::c_char,
+        res: *mut sensors_chip_name,
+    ) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    pub fn sensors_free_chip_name(chip: *mut sensors_chip_name);
+}
+unsafe extern "C" {
+    pub fn sensors_snprintf_chip_name(
+        str_: *mut ::std::os::raw::c_char,
+        size: usize,
+        chip: *const sensors_chip_name,
+    ) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    pub fn sensors_get_adapter_name(bus: *const sensors_bus_id) -> *const ::std::os::raw::c_char;
+}
+unsafe extern "C" {
+    pub fn sensors_get_label(
+        name: *const sensors_chip_name,
+        feature: *const sensors_feature,
+    ) -> *mut ::std::os::raw::c_char;
+}
+unsafe extern "C" {
+    pub fn sensors_get_value(
+        name: *const sensors_chip_name,
+        subfeat_nr: ::std::os::raw::c_int,
+        value: *mut f64,
+    ) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    pub fn sensors_set_value(
+        name: *const sensors_chip_name,
+        subfeat_nr: ::std::os::raw::c_int,
+        value: f64,
+    ) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    pub fn sensors_do_chip_sets(name: *const sensors_chip_name) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    pub fn sensors_get_detected_chips(
+        match_: *const sensors_chip_name,
+        nr: *mut ::std::os::raw::c_int,
+    ) -> *const sensors_chip_name;
+}
+pub mod sensors_feature_type {
+    pub type Type = ::std::os::raw::c_uint;
+    pub const SENSORS_FEATURE_IN: Type = 0;
+    pub const SENSORS_FEATURE_FAN: Type = 1;
+    pub const SENSORS_FEATURE_TEMP: Type = 2;
+    pub const SENSORS_FEATURE_POWER: Type = 3;
+    pub const SENSORS_FEATURE_ENERGY: Type = 4;
+    pub const SENSORS_FEATURE_CURR: Type = 5;
+    pub const SENSORS_FEATURE_HUMIDITY: Type = 6;
+    pub const SENSORS_FEATURE_MAX_MAIN: Type = 7;
+    pub const SENSORS_FEATURE_VID: Type = 16;
+    pub const SENSORS_FEATURE_INTRUSION: Type = 17;
+    pub const SENSORS_FEATURE_MAX_OTHER: Type = 18;
+    pub const SENSORS_FEATURE_BEEP_ENABLE: Type = 24;
+    pub const SENSORS_FEATURE_MAX: Type = 25;
+    pub const SENSORS_FEATURE_UNKNOWN: Type = 2147483647;
+}
+pub mod sensors_subfeature_type {
+    pub type Type = ::std::os::raw::c_uint;
+    pub const SENSORS_SUBFEATURE_IN_INPUT: Type = 0;
+    pub const SENSORS_SUBFEATURE_IN_MIN: Type = 1;
+    pub const SENSORS_SUBFEATURE_IN_MAX: Type = 2;
+    pub const SENSORS_SUBFEATURE_IN_LCRIT: Type = 3;
+    pub const SENSORS_SUBFEATURE_IN_CRIT: Type = 4;
+    pub const SENSORS_SUBFEATURE_IN_AVERAGE: Type = 5;
+    pub const SENSORS_SUBFEATURE_IN_LOWEST: Type = 6;
+    pub const SENSORS_SUBFEATURE_IN_HIGHEST: Type = 7;
+    pub const SENSORS_SUBFEATURE_IN_ALARM: Type = 128;
+    pub const SENSORS_SUBFEATURE_IN_MIN_ALARM: Type = 129;
+    pub const SENSORS_SUBFEATURE_IN_MAX_ALARM: Type = 130;
+    pub const SENSORS_SUBFEATURE_IN_BEEP: Type = 131;
+    pub const SENSORS_SUBFEATURE_IN_LCRIT_ALARM: Type = 132;
+    pub const SENSORS_SUBFEATURE_IN_CRIT_ALARM: Type = 133;
+    pub const SENSORS_SUBFEATURE_FAN_INPUT: Type = 256;
+    pub const SENSORS_SUBFEATURE_FAN_MIN: Type = 257;
+    pub const SENSORS_SUBFEATURE_FAN_MAX: Type = 258;
+    pub const SENSORS_SUBFEATURE_FAN_ALARM: Type = 384;
+    pub const SENSORS_SUBFEATURE_FAN_FAULT: Type = 385;
+    pub const SENSORS_SUBFEATURE_FAN_DIV: Type = 386;
+    pub const SENSORS_SUBFEATURE_FAN_BEEP: Type = 387;
+    pub const SENSORS_SUBFEATURE_FAN_PULSES: Type = 388;
+    pub const SENSORS_SUBFEATURE_FAN_MIN_ALARM: Type = 389;
+    pub const SENSORS_SUBFEATURE_FAN_MAX_ALARM: Type = 390;
+    pub const SENSORS_SUBFEATURE_TEMP_INPUT: Type = 512;
+    pub const SENSORS_SUBFEATURE_TEMP_MAX: Type = 513;
+    pub const SENSORS_SUBFEATURE_TEMP_MAX_HYST: Type = 514;
+    pub const SENSORS_SUBFEATURE_TEMP_MIN: Type = 515;
+    pub const SENSORS_SUBFEATURE_TEMP_CRIT: Type = 516;
+    pub const SENSORS_SUBFEATURE_TEMP_CRIT_HYST: Type = 517;
+    pub const SENSORS_SUBFEATURE_TEMP_LCRIT: Type = 518;
+    pub const SENSORS_SUBFEATURE_TEMP_EMERGENCY: Type = 519;
+    pub const SENSORS_SUBFEATURE_TEMP_EMERGENCY_HYST: Type = 520;
+    pub const SENSORS_SUBFEATURE_TEMP_LOWEST: Type = 521;
+    pub const SENSORS_SUBFEATURE_TEMP_HIGHEST: Type = 522;
+    pub const SENSORS_SUBFEATURE_TEMP_MIN_HYST: Type = 523;
+    pub const SENSORS_SUBFEATURE_TEMP_LCRIT_HYST: Type = 524;
+    pub const SENSORS_SUBFEATURE_TEMP_ALARM: Type = 640;
+    pub const SENSORS_SUBFEATURE_TEMP_MAX_ALARM: Type = 641;
+    pub const SENSORS_SUBFEATURE_TEMP_MIN_ALARM: Type = 642;
+    pub const SENSORS_SUBFEATURE_TEMP_CRIT_ALARM: Type = 643;
+    pub const SENSORS_SUBFEATURE_TEMP_FAULT: Type = 644;
+    pub const SENSORS_SUBFEATURE_TEMP_TYPE: Type = 645;
+    pub const SENSORS_SUBFEATURE_TEMP_OFFSET: Type = 646;
+    pub const SENSORS_SUBFEATURE_TEMP_BEEP: Type = 647;
+    pub const SENSORS_SUBFEATURE_TEMP_EMERGENCY_ALARM: Type = 648;
+    pub const SENSORS_SUBFEATURE_TEMP_LCRIT_ALARM: Type = 649;
+    pub const SENSORS_SUBFEATURE_POWER_AVERAGE: Type = 768;
+    pub const SENSORS_SUBFEATURE_POWER_AVERAGE_HIGHEST: Type = 769;
+    pub const SENSORS_SUBFEATURE_POWER_AVERAGE_LOWEST: Type = 770;
+    pub const SENSORS_SUBFEATURE_POWER_INPUT: Type = 771;
+    pub const SENSORS_SUBFEATURE_POWER_INPUT_HIGHEST: Type = 772;
+    pub const SENSORS_SUBFEATURE_POWER_INPUT_LOWEST: Type = 773;
+    pub const SENSORS_SUBFEATURE_POWER_CAP: Type = 774;
+    pub const SENSORS_SUBFEATURE_POWER_CAP_HYST: Type = 775;
+    pub const SENSORS_SUBFEATURE_POWER_MAX: Type = 776;
+    pub const SENSORS_SUBFEATURE_POWER_CRIT: Type = 777;
+    pub const SENSORS_SUBFEATURE_POWER_MIN: Type = 778;
+    pub const SENSORS_SUBFEATURE_POWER_LCRIT: Type = 779;
+    pub const SENSORS_SUBFEATURE_POWER_AVERAGE_INTERVAL: Type = 896;
+    pub const SENSORS_SUBFEATURE_POWER_ALARM: Type = 897;
+    pub const SENSORS_SUBFEATURE_POWER_CAP_ALARM: Type = 898;
+    pub const SENSORS_SUBFEATURE_POWER_MAX_ALARM: Type = 899;
+    pub const SENSORS_SUBFEATURE_POWER_CRIT_ALARM: Type = 900;
+    pub const SENSORS_SUBFEATURE_POWER_MIN_ALARM: Type = 901;
+    pub const SENSORS_SUBFEATURE_POWER_LCRIT_ALARM: Type = 902;
+    pub const SENSORS_SUBFEATURE_ENERGY_INPUT: Type = 1024;
+    pub const SENSORS_SUBFEATURE_CURR_INPUT: Type = 1280;
+    pub const SENSORS_SUBFEATURE_CURR_MIN: Type = 1281;
+    pub const SENSORS_SUBFEATURE_CURR_MAX: Type = 1282;
+    pub const SENSORS_SUBFEATURE_CURR_LCRIT: Type = 1283;
+    pub const SENSORS_SUBFEATURE_CURR_CRIT: Type = 1284;
+    pub const SENSORS_SUBFEATURE_CURR_AVERAGE: Type = 1285;
+    pub const SENSORS_SUBFEATURE_CURR_LOWEST: Type = 1286;
+    pub const SENSORS_SUBFEATURE_CURR_HIGHEST: Type = 1287;
+    pub const SENSORS_SUBFEATURE_CURR_ALARM: Type = 1408;
+    pub const SENSORS_SUBFEATURE_CURR_MIN_ALARM: Type = 1409;
+    pub const SENSORS_SUBFEATURE_CURR_MAX_ALARM: Type = 1410;
+    pub const SENSORS_SUBFEATURE_CURR_BEEP: Type = 1411;
+    pub const SENSORS_SUBFEATURE_CURR_LCRIT_ALARM: Type = 1412;
+    pub const SENSORS_SUBFEATURE_CURR_CRIT_ALARM: Type = 1413;
+    pub const SENSORS_SUBFEATURE_HUMIDITY_INPUT: Type = 1536;
+    pub const SENSORS_SUBFEATURE_VID: Type = 4096;
+    pub const SENSORS_SUBFEATURE_INTRUSION_ALARM: Type = 4352;
+    pub const SENSORS_SUBFEATURE_INTRUSION_BEEP: Type = 4353;
+    pub const SENSORS_SUBFEATURE_BEEP_ENABLE: Type = 6144;
+    pub const SENSORS_SUBFEATURE_UNKNOWN: Type = 2147483647;
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct sensors_feature {
+    pub name: *mut ::std::os::raw::c_char,
+    pub number: ::std::os::raw::c_int,
+    pub type_: sensors_feature_type::Type,
+    pub first_subfeature: ::std::os::raw::c_int,
+    pub padding1: ::std::os::raw::c_int,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct sensors_subfeature {
+    pub name: *mut ::std::os::raw::c_char,
+    pub number: ::std::os::raw::c_int,
+    pub type_: sensors_subfeature_type::Type,
+    pub mapping: ::std::os::raw::c_int,
+    pub flags: ::std::os::raw::c_uint,
+}
+unsafe extern "C" {
+    pub fn sensors_get_features(
+        name: *const sensors_chip_name,
+        nr: *mut ::std::os::raw::c_int,
+    ) -> *const sensors_feature;
+}
+unsafe extern "C" {
+    pub fn sensors_get_all_subfeatures(
+        name: *const sensors_chip_name,
+        feature: *const sensors_feature,
+        nr: *mut ::std::os::raw::c_int,
+    ) -> *const sensors_subfeature;
+}
+unsafe extern "C" {
+    pub fn sensors_get_subfeature(
+        name: *const sensors_chip_name,
+        feature: *const sensors_feature,
+        type_: sensors_subfeature_type::Type,
+    ) -> *const sensors_subfeature;
+}
+unsafe extern "C" {
+    pub fn sensors_strerror(errnum: ::std::os::raw::c_int) -> *const ::std::os::raw::c_char;
+}
+unsafe extern "C" {
+    pub static mut sensors_parse_error: ::std::option::Option<
+        unsafe extern "C" fn(err: *const ::std::os::raw::c_char, lineno: ::std::os::raw::c_int),
+    >;
+}
+unsafe extern "C" {
+    pub static mut sensors_parse_error_wfn: ::std::option::Option<
+        unsafe extern "C" fn(
+            err: *const ::std::os::raw::c_char,
+            filename: *const ::std::os::raw::c_char,
+            lineno: ::std::os::raw::c_int,
+        ),
+    >;
+}
+unsafe extern "C" {
+    pub static mut sensors_fatal_error: ::std::option::Option<
+        unsafe extern "C" fn(proc_: *const ::std::os::raw::c_char, err: *const ::std::os::raw::c_char),
+    >;
+}
@@ -0,0 +1,114 @@
+use std::{ffi::{c_char, c_int}, fmt::Display, mem, path::PathBuf, process, ptr, sync::Mutex};
+
+#[cfg(not(feature = "link"))]
+use libloading::Library;
+use log::error;
+
+use crate::utils::try_cstr;
+
+#[cfg(not(feature = "link"))]
+type ParseErrorFn = unsafe extern "C" fn(*const c_char, c_int);
+#[cfg(not(feature = "link"))]
+type ParseErrorWfnFn = unsafe extern "C" fn(*const c_char, *const c_char, c_int);
+#[cfg(not(feature = "link"))]
+type FatalErrorFn = unsafe extern "C" fn(*const c_char, *const c_char);
+
+/// An error libsensors reported while parsing its configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConfigParseError {
+    pub message: String,
+    /// The file the error is in, or None if libsensors did not say (e.g. for in-memory configurations).
+    pub file: Option<PathBuf>,
+    /// The line the error is on. 0 if libsensors did not know.
+    pub line: c_int,
+}
+impl Display for ConfigParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{}:{}: {}", file.display(), self.line, self.message),
+            None => write!(f, "line {}: {}", self.line, self.message),
+        }
+    }
+}
+impl std::error::Error for ConfigParseError { }
+
+/// What the callbacks reported since the last call to [`take_reports`].
+#[derive(Debug, Default)]
+pub(crate) struct Reports {
+    pub parse: Vec<ConfigParseError>,
+}
+
+// The callbacks get no user data, but there is only ever one LibSensors, so a global is enough.
+static REPORTS: Mutex<Reports> = Mutex::new(Reports { parse: Vec::new() });
+
+/// Takes everything reported so far, leaving nothing behind.
+pub(crate) fn take_reports() -> Reports {
+    mem::take(&mut *REPORTS.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+fn lossy(s: *const c_char) -> Option<String> {
+    // SAFETY: libsensors passes either null or valid C-strings that live for the duration of the callback.
+    unsafe { try_cstr(s) }.map(|s| s.to_string_lossy().into_owned())
+}
+
+unsafe extern "C" fn parse_error_wfn(err: *const c_char, filename: *const c_char, lineno: c_int) {
+    let error = ConfigParseError {
+        message: lossy(err).unwrap_or_default(),
+        file: lossy(filename).map(PathBuf::from),
+        line: lineno,
+    };
+    REPORTS.lock().unwrap_or_else(|e| e.into_inner()).parse.push(error);
+}
+
+unsafe extern "C" fn parse_error(err: *const c_char, lineno: c_int) {
+    unsafe { parse_error_wfn(err, ptr::null(), lineno) }
+}
+
+/// Replaces the default handler, which prints to stderr and exits the process, so that the error is logged.
+///
+/// libsensors calls this when it runs out of memory and relies on it never returning:
+/// its callers carry on with the failed allocation otherwise. So this aborts after logging.
+unsafe extern "C" fn fatal_error(proc_: *const c_char, err: *const c_char) {
+    let function = lossy(proc_).unwrap_or_default();
+    let message = lossy(err).unwrap_or_default();
+    error!("libsensors fatal error in {function}: {message}");
+    process::abort()
+}
+
+/// Points the error handlers of `lib` to this module.
+///
+/// Versions without `sensors_parse_error_wfn` get the handler without file name instead.
+///
+/// # Safety
+/// `lib` must be libsensors and no other thread may be calling into it.
+#[cfg(not(feature = "link"))]
+pub(crate) unsafe fn install(lib: &Library) {
+    // SAFETY: The handlers are plain global function pointers of these types,
+    //  and nothing reads them concurrently as guaranteed by the caller.
+    unsafe {
+        if let Ok(wfn) = lib.get::<*mut Option<ParseErrorWfnFn>>(c"sensors_parse_error_wfn") {
+            **wfn = Some(parse_error_wfn);
+        } else if let Ok(handler) = lib.get::<*mut Option<ParseErrorFn>>(c"sensors_parse_error") {
+            **handler = Some(parse_error);
+        }
+        if let Ok(handler) = lib.get::<*mut Option<FatalErrorFn>>(c"sensors_fatal_error") {
+            **handler = Some(fatal_error);
+        }
+    }
+}
+
+/// Points the error handlers of the linked libsensors to this module.
+///
+/// # Safety
+/// No other thread may be calling into libsensors.
+#[cfg(feature = "link")]
+pub(crate) unsafe fn install() {
+    use crate::ffi;
+
+    // SAFETY: Nothing reads the handlers concurrently as guaranteed by the caller.
+    unsafe {
+        ffi::sensors_parse_error_wfn = Some(parse_error_wfn);
+        ffi::sensors_parse_error = Some(parse_error);
+        ffi::sensors_fatal_error = Some(fatal_error);
+    }
+}
@@ -1,17 +1,21 @@
-use std::ffi::{CStr, c_int, c_short};
+use std::{ffi::{CStr, OsStr, c_double, c_int, c_short}, fmt::Display, io, os::unix::ffi::OsStrExt, path::{Path, PathBuf}, str::FromStr};
 
-use libloading::Symbol;
+use log::warn;
 
-use crate::{GetFeatures, LibSensors, error::{Error, Result}, feature::Feature, ffi::{self, sensors_bus_id, sensors_chip_name}, utils::{invert_res_opt, ptr_to_ref, try_cstr}};
+use crate::{LibSensors, Version, callbacks, chip_name::ChipName, config::{Expr, Statement, resolve_chip_name}, device::DeviceInfo, error::{Error, ErrorContext, ErrorKind, Operation, Result, SensorsError}, feature::Feature, subfeature::Subfeature, ffi::{self, sensors_bus_id, sensors_chip_name}, utils::{checked_ptr_to_ref, invert_res_opt, try_cstr}};
 
-unsafe fn get_feature_raw<'lib>(
-    fun: &Symbol<'lib, GetFeatures>,
+pub(crate) unsafe fn get_feature_raw<'lib>(
     lib: &'lib LibSensors,
     chip: &'lib sensors_chip_name,
     index: &mut c_int
 ) -> Option<Result<Feature<'lib>>> {
-    unsafe { ptr_to_ref(fun(chip, index)) }.unwrap()
-        .map(|f| Feature::new(lib, chip, f))
+    unsafe { checked_ptr_to_ref((lib._sensors_get_features())(chip, index), "feature") }
+        .transpose()
+        .map(|f| f.and_then(|f| Feature::new(lib, chip, f)).map_err(|e| e.with_context(ErrorContext {
+            // SAFETY: chip always comes from a Chip, whose prefix has been checked in Chip::new.
+            chip: Some(unsafe { ChipName::from_raw(chip) }),
+            ..Default::default()
+        })))
 }
 
 #[derive(Debug)]
@@ -29,13 +33,18 @@ impl<'lib> Chip<'lib> {
             // SAFETY: raw is valid for the lifetime 'lib (matching the lifetime of prefix)
             //  It is valid for reads up to the NUL terminator, coming from libsensors itself.
             //  FIXME: technically nothing prevents raw.prefix from being longer than isize::MAX
-            prefix: unsafe { try_cstr(raw.prefix).expect("chip prefix was null") },
+            prefix: unsafe { try_cstr(raw.prefix) }.ok_or(Error::InvalidPointer { what: "chip prefix", context: None })?,
             bus: BusId::try_from(raw.bus)?
         })
     }
 
-    pub fn get_name_raw(&self) -> Result<Option<&'lib CStr>> {
-        let fun = self.lib._sensors_get_adapter_name()?;
+    /// Describes this chip for errors.
+    pub(crate) fn error_context(&self) -> ErrorContext {
+        ErrorContext { chip: Some(self.name()), ..Default::default() }
+    }
+
+    pub fn get_name_raw(&self) -> Option<&'lib CStr> {
+        let fun = self.lib._sensors_get_adapter_name();
         // SAFETY: I can call sensors_get_adapter_name at any time. There are no safety requirements
         //  The passed pointer trivially lives as long as fun & it isn't stored by fun.
         let raw = unsafe { fun(&self.raw.bus) };
@@ -45,20 +54,40 @@ impl<'lib> Chip<'lib> {
         //   which means that it lives until sensors_cleanup (i.e. the lifetime of self.lib).
         //  libsensors (probably!) doesn't mutate the adapter name for its lifetime (TODO: Verify this)
         //  FIXME: Technically there is nothing preventing strlen(raw) > isize::MAX, even though it is very unlikely.
-        Ok(unsafe { try_cstr(raw) })
+        unsafe { try_cstr(raw) }
     }
 
     pub fn get_name(&self) -> Result<Option<&'lib str>> {
         invert_res_opt(
-            self.get_name_raw()?
+            self.get_name_raw()
                 .map(|c| c.to_str())
-        ).map_err(Into::into)
+        ).map_err(Error::from)
+    }
+
+    /// The full name of this chip (e.g. `coretemp-isa-0000`).
+    pub fn name(&self) -> ChipName {
+        // SAFETY: The prefix has been checked to be a valid C-string in Self::new.
+        unsafe { ChipName::from_raw(self.raw) }
     }
 
     pub fn get_prefix(&self) -> &'lib CStr {
         self.prefix
     }
 
+    /// The sysfs directory of this chip (e.g. `/sys/class/hwmon/hwmon0`).
+    pub fn sysfs_path(&self) -> Option<&'lib Path> {
+        // SAFETY: raw.path comes from libsensors and is either null or a valid C-string
+        //  living as long as the library.
+        unsafe { try_cstr(self.raw.path) }.map(|path| Path::new(OsStr::from_bytes(path.to_bytes())))
+    }
+
+    /// Reads metadata about the device behind this chip from its sysfs directory.
+    pub fn device_info(&self) -> io::Result<DeviceInfo> {
+        let path = self.sysfs_path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "chip has no sysfs path"))?;
+        DeviceInfo::from_sysfs_path(path)
+    }
+
     pub fn get_bus_id(&self) -> BusId {
         self.bus
     }
@@ -68,44 +97,214 @@ impl<'lib> Chip<'lib> {
     }
 
     pub fn get_feature(&self, index: c_int) -> Result<Option<Feature<'lib>>> {
-        self.lib._sensors_get_features()
-            .map_err(Into::into)
-            .and_then(|sym| 
-                invert_res_opt(
-                    unsafe { get_feature_raw(&sym, self.lib, self.raw, &mut index.clone()) }
-                )
-            )
+        invert_res_opt(
+            unsafe { get_feature_raw(self.lib, self.raw, &mut index.clone()) }
+        )
+    }
+
+    /// Finds the subfeature with the given name (e.g. `temp1_input`) among all features of this chip.
+    pub fn get_subfeature_by_name(&self, name: &str) -> Result<Option<Subfeature<'lib>>> {
+        for feature in self.get_features() {
+            for subfeature in feature?.get_subfeatures() {
+                let subfeature = subfeature?;
+                if subfeature.get_name().is_some_and(|n| n.to_bytes() == name.as_bytes()) {
+                    return Ok(Some(subfeature))
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn get_features(&self) -> FeatureIterator<'lib> {
+        FeatureIterator::new(self.lib, self.raw)
+    }
+
+    /// Applies the `set` statements of the configuration to this chip with `sensors_do_chip_sets`, like `sensors -s`.
+    /// 
+    /// libsensors reports every statement that fails. The statements for this chip are taken from the
+    /// configuration files as read during initialisation, so that the others can be listed as applied.
+    /// 
+    /// Writing limits usually requires root.
+    pub fn apply_sets(&self) -> SetsReport {
+        let fun = self.lib._sensors_do_chip_sets();
+        // Anything left over is from a previous call and has already been handled.
+        callbacks::take_reports();
+        // SAFETY: self.raw is a valid chip name from libsensors.
+        let res = unsafe { fun(self.raw) };
+        let reports = callbacks::take_reports().parse;
+        let context = || self.error_context().with_operation(Operation::Set);
+        // The error of the last statement that failed.
+        let last_error = SensorsError::convert_cint_with(self.lib, res).err();
+
+        let mut applied = self.set_statements();
+        let mut failed = Vec::new();
+        for (i, report) in reports.iter().enumerate() {
+            let kind = SetFailure::from_message(&report.message);
+            let statement = applied.iter()
+                .position(|statement| {
+                    // libsensors does not know the name of a file it was handed.
+                    statement.line == report.line as u32 && (report.file.is_none() || report.file == statement.file)
+                })
+                .map(|index| applied.remove(index));
+            let error = match (&kind, &statement) {
+                (SetFailure::UnknownSubfeature, _) => Some(Error::from(ErrorKind::NoEntry)),
+                (SetFailure::Expression, Some(statement)) => self.eval_set(&statement.expression).err(),
+                _ => None,
+            };
+            let error = error
+                .or_else(|| last_error.clone().filter(|_| i + 1 == reports.len()).map(Error::from))
+                .map(|e| e.with_context(context()));
+            warn!("Failed to write to {}: {report}", self.name());
+            failed.push(FailedSet { statement, file: report.file.clone(), line: report.line, kind, error });
+        }
+        SetsReport {
+            chip: self.name(),
+            result: last_error.map_or(Ok(()), |e| Err(Error::from(e).with_context(context()))),
+            applied,
+            failed,
+        }
+    }
+
+    /// The `set` statements for this chip, in the order `sensors_do_chip_sets` applies them.
+    fn set_statements(&self) -> Vec<SetStatement> {
+        let name = self.name();
+        let adapter_bus = |adapter: &str| self.lib.get_chips()
+            .flatten()
+            .map(|chip| (chip.get_bus_id(), chip.get_name_raw()))
+            .find(|(bus, name)| bus.type_ == BusType::I2C && name.is_some_and(|name| name.to_bytes() == adapter.as_bytes()))
+            .map(|(bus, _)| bus.nr);
+
+        let mut blocks: Vec<Vec<SetStatement>> = Vec::new();
+        for file in self.lib.config_files() {
+            let Some(config) = &file.config else { continue };
+            // libsensors resolves the bus statements of each file on its own.
+            let buses: Vec<_> = config.buses().collect();
+            let mut matched = false;
+            for line in &config.lines {
+                match &line.statement {
+                    Some(Statement::Chip { names }) => {
+                        matched = names.iter().any(|pattern| {
+                            resolve_chip_name(pattern, &buses, adapter_bus).is_some_and(|pattern| pattern.matches(&name))
+                        });
+                        if matched {
+                            blocks.push(Vec::new());
+                        }
+                    },
+                    Some(Statement::Set { subfeature, value }) => if let Some(block) = blocks.last_mut().filter(|_| matched) {
+                        block.push(SetStatement {
+                            subfeature: subfeature.clone(),
+                            expression: value.clone(),
+                            file: file.path.clone(),
+                            line: line.span.line,
+                        });
+                    },
+                    _ => {},
+                }
+            }
+        }
+        // libsensors goes through the blocks from the last to the first.
+        blocks.into_iter().rev().flatten().collect()
+    }
+
+    /// Evaluates the expression of a `set` statement like libsensors, to find out why it failed there.
+    fn eval_set(&self, expression: &Expr) -> Result<c_double> {
+        expression.eval(0.0, &mut |name| {
+            self.get_subfeature_by_name(name)?
+                .ok_or(Error::from(ErrorKind::NoEntry))?
+                .get_value()
+        })
+    }
+}
+
+/// A `set` statement of the configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetStatement {
+    /// The subfeature it writes (e.g. `in0_min`).
+    pub subfeature: String,
+    pub expression: Expr,
+    /// The file it is in, or None for an in-memory configuration.
+    pub file: Option<PathBuf>,
+    pub line: u32,
+}
+
+/// Which step of a `set` statement failed, as reported by libsensors.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SetFailure {
+    /// The chip has no subfeature of that name.
+    UnknownSubfeature,
+    /// The expression could not be evaluated (e.g. it divides by zero).
+    Expression,
+    /// Writing the value failed (e.g. without root).
+    Write,
+    /// A message this crate does not know.
+    Other(String),
+}
+impl SetFailure {
+    fn from_message(message: &str) -> Self {
+        match message {
+            "Unknown feature name" => Self::UnknownSubfeature,
+            "Error parsing expression" => Self::Expression,
+            "Failed to set value" => Self::Write,
+            _ => Self::Other(message.to_owned()),
+        }
     }
+}
+
+/// A `set` statement that libsensors could not apply.
+#[derive(Debug)]
+pub struct FailedSet {
+    /// The statement, or None if it is in a file this crate could not parse.
+    pub statement: Option<SetStatement>,
+    /// The file of the statement, if libsensors knows it.
+    pub file: Option<PathBuf>,
+    pub line: c_int,
+    pub kind: SetFailure,
+    /// The error, if known. libsensors only returns the error of the last statement that failed,
+    /// so for the others it is [`ErrorKind::NoEntry`] for unknown subfeatures, the error of evaluating the
+    /// expression again, or None.
+    pub error: Option<Error>,
+}
 
-    pub fn get_features(&self) -> Result<FeatureIterator<'lib>> {
-        self.lib._sensors_get_features()
-            .map_err(Into::into)
-            .map(|sym| FeatureIterator::new(self.lib, sym, self.raw))
+/// The outcome of applying the `set` statements to a chip.
+#[derive(Debug)]
+pub struct SetsReport {
+    pub chip: ChipName,
+    /// Ok if every statement was applied, otherwise the error of the last one that failed.
+    pub result: Result<()>,
+    /// The statements that were applied, in the order libsensors applied them.
+    /// 
+    /// Statements in files this crate could not parse are missing.
+    pub applied: Vec<SetStatement>,
+    pub failed: Vec<FailedSet>,
+}
+impl SetsReport {
+    pub fn is_ok(&self) -> bool {
+        self.result.is_ok() && self.failed.is_empty()
     }
 }
 
 pub struct FeatureIterator<'lib> {
     lib: &'lib LibSensors,
-    fun: Symbol<'lib, GetFeatures>,
     chip: &'lib sensors_chip_name,
     index: c_int
 }
 impl<'lib> FeatureIterator<'lib> {
-    pub fn new(lib: &'lib LibSensors, fun: Symbol<'lib, GetFeatures>, chip: &'lib sensors_chip_name) -> Self {
-        Self { lib, fun, chip, index: 0 }
+    pub fn new(lib: &'lib LibSensors, chip: &'lib sensors_chip_name) -> Self {
+        Self { lib, chip, index: 0 }
     }
 }
 impl<'lib> Iterator for FeatureIterator<'lib> {
     type Item = Result<Feature<'lib>>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        unsafe { get_feature_raw(&self.fun, self.lib, self.chip, &mut self.index) }
+        unsafe { get_feature_raw(self.lib, self.chip, &mut self.index) }
     }
 }
 
 
 #[repr(i8)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, strum::IntoStaticStr)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, strum::IntoStaticStr)]
 /// The type of a bus, excluding any wildcard values
 pub enum BusType {
     I2C = 0,
@@ -132,11 +331,66 @@ impl TryFrom<c_short> for BusType {
             ffi::SENSORS_BUS_TYPE_HID     => Self::HID,
             ffi::SENSORS_BUS_TYPE_MDIO    => Self::MDIO,
             ffi::SENSORS_BUS_TYPE_SCSI    => Self::SCSI,
-            x => return Err(Error::UnexpectedWildcard(x as i64))
+            x => return Err(Error::parse("bus number", &x.to_string()))
+        })
+    }
+}
+impl BusType {
+    /// The name libsensors uses for this bus type in chip names (e.g. `i2c` or `isa`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::I2C     => "i2c",
+            Self::ISA     => "isa",
+            Self::PCI     => "pci",
+            Self::SPI     => "spi",
+            Self::VIRTUAL => "virtual",
+            Self::ACPI    => "acpi",
+            Self::HID     => "hid",
+            Self::MDIO    => "mdio",
+            Self::SCSI    => "scsi",
+        }
+    }
+
+    /// Whether buses of this type are numbered in chip names (e.g. `i2c-3`).
+    pub fn has_number(self) -> bool {
+        matches!(self, Self::I2C | Self::SPI | Self::HID | Self::SCSI)
+    }
+
+    /// The first libsensors version that knows this bus type.
+    pub fn min_version(self) -> Version {
+        match self {
+            Self::I2C | Self::ISA | Self::PCI | Self::SPI | Self::VIRTUAL => Version::new(3, 0, 0),
+            Self::ACPI => Version::new(3, 0, 3),
+            Self::HID => Version::new(3, 2, 0),
+            Self::MDIO => Version::new(3, 4, 0),
+            Self::SCSI => Version::new(3, 6, 0),
+        }
+    }
+}
+impl Display for BusType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+impl FromStr for BusType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(match s {
+            "i2c"     => Self::I2C,
+            "isa"     => Self::ISA,
+            "pci"     => Self::PCI,
+            "spi"     => Self::SPI,
+            "virtual" => Self::VIRTUAL,
+            "acpi"    => Self::ACPI,
+            "hid"     => Self::HID,
+            "mdio"    => Self::MDIO,
+            "scsi"    => Self::SCSI,
+            _ => return Err(Error::parse("bus type", s))
         })
     }
 }
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 /// The Id of a bus hosting a Chip.
 /// Note that this struct does not support wildcard values
 pub struct BusId {
@@ -155,4 +409,178 @@ impl TryFrom<sensors_bus_id> for BusId {
             nr: value.nr
         })
     }
-}
\ No newline at end of file
+}
+impl From<BusId> for sensors_bus_id {
+    fn from(value: BusId) -> Self {
+        Self { type_: value.type_ as c_short, nr: value.nr }
+    }
+}
+impl Display for BusId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        BusPattern::from(*self).fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The number part of a [`BusPattern`].
+pub enum BusNr {
+    /// Matches any bus number (`SENSORS_BUS_NR_ANY`).
+    Any,
+    /// The bus number is not relevant for this bus (`SENSORS_BUS_NR_IGNORE`).
+    Ignore,
+    Exact(c_short),
+}
+impl TryFrom<c_short> for BusNr {
+    type Error = Error;
+
+    fn try_from(value: c_short) -> std::result::Result<Self, Self::Error> {
+        Ok(match value {
+            ffi::SENSORS_BUS_NR_ANY    => Self::Any,
+            ffi::SENSORS_BUS_NR_IGNORE => Self::Ignore,
+            x if x >= 0                => Self::Exact(x),
+            x => return Err(Error::parse("bus number", &x.to_string()))
+        })
+    }
+}
+impl From<BusNr> for c_short {
+    fn from(value: BusNr) -> Self {
+        match value {
+            BusNr::Any => ffi::SENSORS_BUS_NR_ANY,
+            BusNr::Ignore => ffi::SENSORS_BUS_NR_IGNORE,
+            BusNr::Exact(nr) => nr,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// A bus identifier that may contain wildcards, as used in chip patterns and `bus` statements.
+/// 
+/// The string form is `type-nr` (e.g. `i2c-3`), where the type may be `*` for any type
+/// and the number may be `*` for any number or be omitted if it does not matter (e.g. `isa`).
+pub struct BusPattern {
+    /// The type of the bus, or None if any type matches.
+    pub type_: Option<BusType>,
+    pub nr: BusNr
+}
+impl BusPattern {
+    /// A pattern matching any bus.
+    pub const ANY: Self = Self { type_: None, nr: BusNr::Any };
+
+    /// Whether `bus` is matched by this pattern.
+    pub fn matches(&self, bus: BusId) -> bool {
+        self.type_.is_none_or(|t| t == bus.type_)
+            && match self.nr {
+                BusNr::Any | BusNr::Ignore => true,
+                BusNr::Exact(nr) => nr == bus.nr,
+            }
+    }
+}
+impl TryFrom<sensors_bus_id> for BusPattern {
+    type Error = Error;
+
+    fn try_from(value: sensors_bus_id) -> std::result::Result<Self, Self::Error> {
+        Ok(Self {
+            type_: match value.type_ {
+                ffi::SENSORS_BUS_TYPE_ANY => None,
+                x => Some(BusType::try_from(x)?)
+            },
+            nr: BusNr::try_from(value.nr)?
+        })
+    }
+}
+impl From<BusPattern> for sensors_bus_id {
+    fn from(value: BusPattern) -> Self {
+        Self {
+            type_: value.type_.map_or(ffi::SENSORS_BUS_TYPE_ANY, |t| t as c_short),
+            nr: value.nr.into()
+        }
+    }
+}
+impl From<BusId> for BusPattern {
+    fn from(value: BusId) -> Self {
+        Self { type_: Some(value.type_), nr: BusNr::Exact(value.nr) }
+    }
+}
+impl TryFrom<BusPattern> for BusId {
+    type Error = Error;
+
+    fn try_from(value: BusPattern) -> std::result::Result<Self, Self::Error> {
+        BusId::try_from(sensors_bus_id::from(value))
+    }
+}
+impl Display for BusPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.type_ {
+            Some(type_) => write!(f, "{type_}")?,
+            None => write!(f, "*")?,
+        }
+        match self.nr {
+            BusNr::Any => write!(f, "-*"),
+            BusNr::Ignore => Ok(()),
+            BusNr::Exact(nr) => write!(f, "-{nr}"),
+        }
+    }
+}
+impl FromStr for BusPattern {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let bus_name_error = || Error::parse("bus name", s);
+        let (type_, nr) = match s.split_once('-') {
+            Some((type_, nr)) => (type_, Some(nr)),
+            None => (s, None),
+        };
+        Ok(Self {
+            type_: match type_ {
+                "*" => None,
+                x => Some(x.parse()?)
+            },
+            nr: match nr {
+                None => BusNr::Ignore,
+                Some("*") => BusNr::Any,
+                Some(nr) => BusNr::Exact(
+                    nr.parse::<c_short>().ok().filter(|nr| *nr >= 0).ok_or_else(bus_name_error)?
+                ),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bus_patterns_round_trip() {
+        for (s, type_, nr) in [
+            ("i2c-1", Some(BusType::I2C), BusNr::Exact(1)),
+            ("i2c-*", Some(BusType::I2C), BusNr::Any),
+            ("isa", Some(BusType::ISA), BusNr::Ignore),
+            ("*-*", None, BusNr::Any),
+            ("*", None, BusNr::Ignore),
+            ("hid-3", Some(BusType::HID), BusNr::Exact(3)),
+        ] {
+            let pattern: BusPattern = s.parse().unwrap();
+            assert_eq!(pattern, BusPattern { type_, nr }, "{s}");
+            assert_eq!(pattern.to_string(), s);
+        }
+    }
+
+    #[test]
+    fn invalid_bus_patterns() {
+        for s in ["", "i2c-", "i2c--1", "i2c-x", "i2c-99999", "foo-1", "-1"] {
+            assert!(matches!(s.parse::<BusPattern>(), Err(Error::Parse { .. })), "{s}");
+        }
+    }
+
+    #[test]
+    fn bus_numbers() {
+        assert_eq!(BusNr::try_from(ffi::SENSORS_BUS_NR_ANY).unwrap(), BusNr::Any);
+        assert_eq!(BusNr::try_from(ffi::SENSORS_BUS_NR_IGNORE).unwrap(), BusNr::Ignore);
+        assert_eq!(BusNr::try_from(5).unwrap(), BusNr::Exact(5));
+        assert!(matches!(BusNr::try_from(-5), Err(Error::Parse { what: "bus number", .. })));
+        for nr in [BusNr::Any, BusNr::Ignore, BusNr::Exact(0)] {
+            assert_eq!(BusNr::try_from(c_short::from(nr)).unwrap(), nr);
+        }
+    }
+}
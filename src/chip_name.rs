@@ -0,0 +1,267 @@
+use std::{ffi::{CStr, CString, c_int, c_short}, fmt::Display, ptr, str::FromStr};
+
+use crate::{chip::{BusNr, BusPattern, BusType}, error::Error, ffi::{self, sensors_bus_id, sensors_chip_name}, utils::try_cstr};
+
+/// The bus number stored for chips on buses of type `bus_type`.
+///
+/// libsensors reports detected chips on unnumbered buses (e.g. ISA or PCI) with bus number 0,
+/// but parses their names with any bus number. Both are stored as 0, so that they compare equal.
+fn normalise_bus_nr(bus_type: c_short, nr: c_short) -> c_short {
+    match BusType::try_from(bus_type) {
+        Ok(bus_type) if !bus_type.has_number() => 0,
+        _ => nr,
+    }
+}
+
+/// An owned chip name, as used by libsensors in the form `prefix-bus-address` (e.g. `coretemp-isa-0000`).
+///
+/// Every part of the name may be a wildcard, so this can also represent
+/// names like `coretemp-*` or `*-pci-*`.
+///
+/// [`FromStr`] and [`Display`] follow the rules of `sensors_parse_chip_name` and `sensors_snprintf_chip_name`,
+/// but do not require libsensors and also work for wildcards.
+/// Use [`crate::LibSensors::parse_chip_name`] and [`crate::LibSensors::format_chip_name`]
+/// to go through libsensors itself.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChipName {
+    prefix: Option<CString>,
+    bus_type: c_short,
+    bus_nr: c_short,
+    addr: c_int,
+}
+/// A [`ChipName`] used to select chips, typically containing wildcards (e.g. `it87-*` or `*-isa-*`).
+pub type ChipPattern = ChipName;
+
+impl ChipName {
+    /// Copies a [`sensors_chip_name`] into an owned [`ChipName`].
+    ///
+    /// # Safety
+    /// `raw.prefix` must either be null or uphold all the safety requirements for [`CStr::from_ptr`].
+    pub(crate) unsafe fn from_raw(raw: &sensors_chip_name) -> Self {
+        Self {
+            // SAFETY: Guaranteed by the caller.
+            prefix: unsafe { try_cstr(raw.prefix) }.map(CStr::to_owned),
+            bus_type: raw.bus.type_,
+            bus_nr: normalise_bus_nr(raw.bus.type_, raw.bus.nr),
+            addr: raw.addr,
+        }
+    }
+
+    /// Creates a [`sensors_chip_name`] borrowing from this name.
+    ///
+    /// The result must not be passed to any libsensors function that writes to or frees the prefix
+    /// and must not outlive `self`.
+    pub(crate) fn as_raw(&self) -> sensors_chip_name {
+        sensors_chip_name {
+            prefix: self.prefix.as_ref().map_or(ptr::null_mut(), |p| p.as_ptr() as *mut _),
+            bus: sensors_bus_id { type_: self.bus_type, nr: self.bus_nr },
+            addr: self.addr,
+            path: ptr::null_mut(),
+        }
+    }
+
+    /// This name on bus number `nr` instead.
+    pub(crate) fn with_bus_nr(mut self, nr: c_short) -> Self {
+        self.bus_nr = normalise_bus_nr(self.bus_type, nr);
+        self
+    }
+
+    /// The chip prefix (i.e. the driver name), or None if any prefix matches.
+    pub fn prefix(&self) -> Option<&CStr> {
+        self.prefix.as_deref()
+    }
+
+    /// The type of the bus, or None if any bus type matches.
+    pub fn bus_type(&self) -> Option<BusType> {
+        BusType::try_from(self.bus_type).ok()
+    }
+
+    /// The bus of the chip, possibly containing wildcards.
+    ///
+    /// Unnumbered buses (e.g. ISA) have [`BusNr::Ignore`].
+    pub fn bus(&self) -> BusPattern {
+        let type_ = self.bus_type();
+        let nr = match type_ {
+            Some(type_) if !type_.has_number() => BusNr::Ignore,
+            _ => BusNr::try_from(self.bus_nr).unwrap_or(BusNr::Any),
+        };
+        BusPattern { type_, nr }
+    }
+
+    /// The number of the bus, or None if any bus number matches.
+    ///
+    /// Bus types without a number in the chip name (e.g. ISA or PCI) always have number 0,
+    /// like the chips libsensors detects on them.
+    pub fn bus_nr(&self) -> Option<c_short> {
+        Some(self.bus_nr).filter(|nr| *nr >= 0)
+    }
+
+    /// The address of the chip on its bus, or None if any address matches.
+    pub fn address(&self) -> Option<c_int> {
+        Some(self.addr).filter(|addr| *addr != ffi::SENSORS_CHIP_NAME_ADDR_ANY)
+    }
+
+    /// Whether any part of this name is a wildcard.
+    pub fn has_wildcards(&self) -> bool {
+        self.prefix.is_none()
+            || self.bus_type == ffi::SENSORS_BUS_TYPE_ANY
+            || self.bus_nr == ffi::SENSORS_BUS_NR_ANY
+            || self.addr == ffi::SENSORS_CHIP_NAME_ADDR_ANY
+    }
+
+    /// Whether `name` is matched by this pattern, following `sensors_match_chip`.
+    ///
+    /// A wildcard on either side matches anything, so this is symmetric.
+    pub fn matches(&self, name: &ChipName) -> bool {
+        fn part<T: PartialEq>(a: T, b: T, any: T) -> bool {
+            a == any || b == any || a == b
+        }
+        let prefix = match (&self.prefix, &name.prefix) {
+            (Some(a), Some(b)) => a == b,
+            _ => true,
+        };
+        prefix
+            && part(self.bus_type, name.bus_type, ffi::SENSORS_BUS_TYPE_ANY)
+            && part(self.bus_nr, name.bus_nr, ffi::SENSORS_BUS_NR_ANY)
+            && part(self.addr, name.addr, ffi::SENSORS_CHIP_NAME_ADDR_ANY)
+    }
+}
+impl FromStr for ChipName {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let chip_name_error = || Error::parse("chip name", s);
+
+        // First, the prefix. It's either "*" or a real chip name.
+        let (prefix, rest) = match s.strip_prefix("*-") {
+            Some(rest) => (None, rest),
+            None => {
+                let (prefix, rest) = s.split_once('-').ok_or_else(chip_name_error)?;
+                (Some(CString::new(prefix).map_err(|_| chip_name_error())?), rest)
+            }
+        };
+
+        // Then we have either a sole "*" (all chips with this prefix) or a bus type and an address.
+        if rest == "*" {
+            return Ok(Self {
+                prefix,
+                bus_type: ffi::SENSORS_BUS_TYPE_ANY,
+                bus_nr: ffi::SENSORS_BUS_NR_ANY,
+                addr: ffi::SENSORS_CHIP_NAME_ADDR_ANY,
+            })
+        }
+        let (bus_type, rest) = rest.split_once('-').ok_or_else(chip_name_error)?;
+        let bus_type: BusType = bus_type.parse().map_err(|_| chip_name_error())?;
+
+        // Some bus types have an additional bus number, which is either "*" or a decimal number.
+        let (bus_nr, addr) = if bus_type.has_number() {
+            let (nr, addr) = rest.split_once('-').ok_or_else(chip_name_error)?;
+            let nr = if nr == "*" {
+                ffi::SENSORS_BUS_NR_ANY
+            } else {
+                nr.parse::<c_short>().ok().filter(|nr| *nr >= 0).ok_or_else(chip_name_error)?
+            };
+            (nr, addr)
+        } else {
+            (normalise_bus_nr(bus_type as c_short, ffi::SENSORS_BUS_NR_ANY), rest)
+        };
+
+        // Last part is the chip address, or "*" for any address.
+        let addr = if addr == "*" {
+            ffi::SENSORS_CHIP_NAME_ADDR_ANY
+        } else {
+            let hex = addr.strip_prefix("0x").or_else(|| addr.strip_prefix("0X")).unwrap_or(addr);
+            c_int::from_str_radix(hex, 16).ok().filter(|addr| *addr >= 0).ok_or_else(chip_name_error)?
+        };
+
+        Ok(Self { prefix, bus_type: bus_type as c_short, bus_nr, addr })
+    }
+}
+impl Display for ChipName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.prefix {
+            Some(prefix) => write!(f, "{}", prefix.to_string_lossy())?,
+            None => write!(f, "*")?,
+        }
+        let Some(bus_type) = self.bus_type() else {
+            return write!(f, "-*")
+        };
+        write!(f, "-{bus_type}")?;
+        if bus_type.has_number() {
+            match self.bus_nr() {
+                Some(nr) => write!(f, "-{nr}")?,
+                None => write!(f, "-*")?,
+            }
+        }
+        match self.address() {
+            None => write!(f, "-*"),
+            Some(addr) => match bus_type {
+                BusType::ISA | BusType::PCI => write!(f, "-{addr:04x}"),
+                BusType::I2C => write!(f, "-{addr:02x}"),
+                _ => write!(f, "-{addr:x}"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::{BuildHasher, RandomState};
+
+    use super::*;
+
+    /// A name on every bus type, as libsensors prints it, and the bus number it reports for such a chip.
+    const NAMES: &[(&str, c_short)] = &[
+        ("lm75-i2c-1-48", 1),
+        ("it87-isa-0290", 0),
+        ("k10temp-pci-00c3", 0),
+        ("max1111-spi-0-1", 0),
+        ("acpitz-virtual-0", 0),
+        ("acpi_fan-acpi-0", 0),
+        ("corsaircpro-hid-3-6", 3),
+        ("lan88xx-mdio-8", 0),
+        ("drivetemp-scsi-0-0", 0),
+    ];
+
+    /// What libsensors reports for a detected chip with the given name.
+    fn detected(name: &ChipName, nr: c_short) -> ChipName {
+        let raw = sensors_chip_name {
+            bus: sensors_bus_id { type_: name.bus_type, nr },
+            ..name.as_raw()
+        };
+        unsafe { ChipName::from_raw(&raw) }
+    }
+
+    #[test]
+    fn round_trip_on_every_bus_type() {
+        for &(s, nr) in NAMES {
+            let name: ChipName = s.parse().unwrap();
+            assert_eq!(name.to_string(), s);
+            assert!(!name.has_wildcards(), "{s}");
+
+            let detected = detected(&name, nr);
+            assert_eq!(detected.to_string(), s);
+            assert_eq!(detected, name, "{s}");
+            let hasher = RandomState::new();
+            assert_eq!(hasher.hash_one(&detected), hasher.hash_one(&name), "{s}");
+            assert!(name.matches(&detected));
+        }
+    }
+
+    #[test]
+    fn unnumbered_buses_ignore_the_number() {
+        let name: ChipName = "it87-isa-0290".parse().unwrap();
+        assert_eq!(name.bus_nr(), Some(0));
+        assert_eq!(name.bus().to_string(), "isa");
+        assert_eq!(detected(&name, ffi::SENSORS_BUS_NR_ANY), name);
+    }
+
+    #[test]
+    fn wildcards_round_trip() {
+        for s in ["*-*", "coretemp-*", "*-isa-*", "*-i2c-*-*", "lm75-i2c-1-*", "*-pci-00c3"] {
+            let name: ChipName = s.parse().unwrap();
+            assert_eq!(name.to_string(), s);
+            assert!(name.has_wildcards(), "{s}");
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! Reading and writing libsensors configuration files (`sensors.conf` and `sensors.d/*.conf`)
+//! without going through libsensors.
+
+use std::{ffi::c_short, fs, path::{Path, PathBuf}, str::FromStr};
+
+use log::warn;
+
+use crate::{BusType, ChipInfo, ChipName, ConfigSource};
+
+mod ast;
+mod builder;
+mod lint;
+mod parser;
+mod writer;
+
+pub use ast::{BinOp, Expr, Line, Span, Statement};
+pub use builder::ConfigBuilder;
+pub use lint::{Diagnostic, DiagnosticKind};
+pub use parser::ParseError;
+
+/// The configuration file libsensors reads by default.
+pub const SYSTEM_FILE: &str = "/etc/sensors3.conf";
+/// The file libsensors reads instead if [`SYSTEM_FILE`] does not exist.
+pub const ALT_SYSTEM_FILE: &str = "/etc/sensors.conf";
+/// The directory whose files libsensors reads after [`SYSTEM_FILE`].
+pub const SYSTEM_DIR: &str = "/etc/sensors.d";
+
+/// The files libsensors reads for [`ConfigSource::Default`], in the same order.
+///
+/// That is [`SYSTEM_FILE`] (or [`ALT_SYSTEM_FILE`] if it does not exist), then the files in [`SYSTEM_DIR`]
+/// sorted by name, leaving out names starting with a dot and anything that is not a regular file.
+/// Files that do not exist are left out.
+pub fn system_files() -> Vec<PathBuf> {
+    // A file whose existence cannot be checked is kept, so that reading it reports why.
+    let mut files: Vec<PathBuf> = [SYSTEM_FILE, ALT_SYSTEM_FILE].into_iter()
+        .map(PathBuf::from)
+        .find(|path| path.try_exists().unwrap_or(true))
+        .into_iter()
+        .collect();
+    if let Ok(dir) = fs::read_dir(SYSTEM_DIR) {
+        let mut extra: Vec<PathBuf> = dir
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| !entry.file_name().as_encoded_bytes().starts_with(b"."))
+            .map(|entry| entry.path())
+            // Follows symlinks, like the stat call of libsensors.
+            .filter(|path| path.is_file())
+            .collect();
+        extra.sort();
+        files.extend(extra);
+    }
+    files
+}
+
+/// A parsed configuration file.
+///
+/// [`Display`](std::fmt::Display) writes it back, keeping comments, blank lines and indentation.
+/// Lines that were parsed and not modified are written exactly as they were.
+/// Other lines are written in a canonical form (e.g. `set in0_min 3.3*0.9`).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    pub lines: Vec<Line>,
+}
+impl Config {
+    /// Parses `src` with the grammar of libsensors.
+    ///
+    /// Like libsensors, this rejects statements other than `bus` before the first `chip` statement.
+    pub fn parse(src: &str) -> Result<Self, ParseError> {
+        parser::parse(src)
+    }
+
+    pub fn statements(&self) -> impl Iterator<Item = &Statement> {
+        self.lines.iter().filter_map(|line| line.statement.as_ref())
+    }
+
+    /// Appends `statement` on a line of its own.
+    pub fn push(&mut self, statement: Statement) {
+        self.lines.push(Line::statement(statement));
+    }
+
+    /// Checks this configuration against `chips`, typically those of a [`Snapshot`](crate::Snapshot).
+    ///
+    /// Reports chip names matching none of the chips, statements referring to features or subfeatures
+    /// a matched chip does not have, and `compute` statements whose expressions are not each other's inverse.
+    /// Like in libsensors, I2C bus numbers in chip names refer to the `bus` statements of this configuration,
+    /// which are resolved through the adapter names of `chips`.
+    /// `file` is only used to label the diagnostics.
+    ///
+    /// libsensors hides ignored features, so `chips` should be read with a configuration that does not ignore any
+    /// (see [`LibSensors::lint_config`](crate::LibSensors::lint_config)).
+    pub fn lint(&self, file: Option<&Path>, chips: &[ChipInfo]) -> Vec<Diagnostic> {
+        lint::lint(self, file, chips)
+    }
+
+    /// The I2C bus numbers and adapter names of the `bus` statements.
+    pub(crate) fn buses(&self) -> impl Iterator<Item = (c_short, &str)> {
+        self.statements().filter_map(|statement| match statement {
+            Statement::Bus { bus, adapter } => Some((bus.strip_prefix("i2c-")?.parse().ok()?, adapter.as_str())),
+            _ => None,
+        })
+    }
+}
+
+/// Parses a name of a `chip` statement, resolving its I2C bus number like `sensors_substitute_busses`.
+///
+/// A bus number in a chip name refers to the `bus` statement for that number in `buses`,
+/// and thus to the detected bus whose adapter has the name given there, which `adapter_bus` looks up.
+/// Returns None if libsensors ignores the name: it does not parse, or its bus is not declared or not present.
+pub(crate) fn resolve_chip_name(
+    name: &str,
+    buses: &[(c_short, &str)],
+    adapter_bus: impl FnOnce(&str) -> Option<c_short>,
+) -> Option<ChipName> {
+    let name: ChipName = name.parse().ok()?;
+    let (Some(bus_type), Some(nr)) = (name.bus_type(), name.bus_nr()) else { return Some(name) };
+    if !bus_type.has_number() {
+        return Some(name)
+    }
+    // Only I2C buses can be declared.
+    if bus_type != BusType::I2C {
+        return None
+    }
+    let (_, adapter) = buses.iter().find(|(bus, _)| *bus == nr)?;
+    Some(name.with_bus_nr(adapter_bus(adapter)?))
+}
+
+/// A file of the configuration libsensors was initialised with, as parsed by this crate.
+#[derive(Debug, Clone)]
+pub(crate) struct ConfigFile {
+    /// The path, or None for [`ConfigSource::Memory`].
+    pub path: Option<PathBuf>,
+    /// None if the file could not be read or parsed by this crate, which has been logged.
+    pub config: Option<Config>,
+}
+
+impl ConfigSource {
+    /// Reads and parses the files libsensors reads for this source, in the same order.
+    ///
+    /// Meant to be called right after libsensors has read them, so that both see the same contents.
+    pub(crate) fn read_files(&self) -> Vec<ConfigFile> {
+        let sources = match self {
+            Self::Default => system_files().into_iter().map(|path| (Some(path), None)).collect(),
+            Self::Path(path) => vec![(Some(path.clone()), None)],
+            Self::Memory(buf) => vec![(None, Some(buf.clone()))],
+        };
+        sources.into_iter()
+            .map(|(path, buf)| {
+                let name = path.as_deref().map_or("<memory>".into(), Path::to_string_lossy);
+                let buf = match (buf, &path) {
+                    (Some(buf), _) => Some(buf),
+                    (None, Some(path)) => fs::read(path)
+                        .inspect_err(|e| warn!("Failed to read configuration {name}: {e}"))
+                        .ok(),
+                    (None, None) => None,
+                };
+                // Labels are the only free text, so they are not worth refusing a file for.
+                let config = buf.and_then(|buf| Config::parse(&String::from_utf8_lossy(&buf))
+                    .inspect_err(|e| warn!("Failed to parse configuration {name}: {e}"))
+                    .ok());
+                ConfigFile { path, config }
+            })
+            .collect()
+    }
+}
+impl FromStr for Config {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+impl FromStr for Expr {
+    type Err = ParseError;
+
+    /// Parses a lone expression (e.g. `@*2`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parser::parse_expr(s)
+    }
+}
+
+impl From<&Config> for ConfigSource {
+    fn from(config: &Config) -> Self {
+        Self::Memory(config.to_string().into_bytes())
+    }
+}
+impl From<Config> for ConfigSource {
+    fn from(config: Config) -> Self {
+        Self::from(&config)
+    }
+}
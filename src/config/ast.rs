@@ -0,0 +1,216 @@
+use std::result::Result as StdResult;
+
+use crate::{ChipPattern, error::{ErrorKind, Result}};
+
+/// Where a part of a configuration was found in the source text.
+///
+/// Parts created in code (rather than parsed) have the default span, with line 0.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Span {
+    /// Byte offset of the first character.
+    pub start: usize,
+    /// Byte offset after the last character.
+    pub end: usize,
+    /// The line of the first character, starting at 1 like libsensors.
+    pub line: u32,
+}
+
+/// One line of a configuration file: a statement, a comment, both or neither.
+///
+/// Lines compare equal if their indentation, statement and comment are equal, regardless of where they came from.
+#[derive(Debug, Clone)]
+pub struct Line {
+    /// The whitespace before the statement or comment.
+    pub indent: String,
+    pub statement: Option<Statement>,
+    /// The comment at the end of the line, without the leading `#`.
+    pub comment: Option<String>,
+    pub span: Span,
+    /// The text this line was parsed from, so that it can be written back unchanged if it was not modified.
+    pub(super) source: Option<Source>,
+}
+#[derive(Debug, Clone)]
+pub(super) struct Source {
+    pub statement: Option<Statement>,
+    pub comment: Option<String>,
+    /// Everything after the indentation, up to the end of the line.
+    pub text: String,
+    /// The spans of the arguments of the statement.
+    pub args: Vec<Span>,
+}
+impl PartialEq for Line {
+    fn eq(&self, other: &Self) -> bool {
+        self.indent == other.indent && self.statement == other.statement && self.comment == other.comment
+    }
+}
+impl Line {
+    /// A line containing only `statement`, indented the way `sensors.conf` usually is.
+    pub fn statement(statement: Statement) -> Self {
+        let indent = if statement.is_chip_scoped() { "    " } else { "" };
+        Self { indent: indent.to_owned(), statement: Some(statement), comment: None, span: Span::default(), source: None }
+    }
+
+    /// A line containing only a comment. `text` must not contain a newline.
+    pub fn comment(text: impl Into<String>) -> Self {
+        Self { indent: String::new(), statement: None, comment: Some(text.into()), span: Span::default(), source: None }
+    }
+
+    pub fn blank() -> Self {
+        Self { indent: String::new(), statement: None, comment: None, span: Span::default(), source: None }
+    }
+
+    /// Where the argument at `index` of the statement was found, e.g. 0 for the feature of a `label` statement
+    /// or 1 for the expression of a `set` statement. The keyword and the comma of `compute` are not arguments.
+    ///
+    /// None if the statement was not parsed or has been modified since.
+    pub fn arg_span(&self, index: usize) -> Option<Span> {
+        self.source.as_ref()
+            .filter(|source| source.statement == self.statement)
+            .and_then(|source| source.args.get(index).copied())
+    }
+}
+
+/// A statement of the libsensors configuration language.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement {
+    /// `bus "i2c-0" "SMBus I801 adapter"`: ties a bus number to an adapter name.
+    Bus { bus: String, adapter: String },
+    /// `chip "it87-*" "w83627hf-*"`: the following statements apply to chips matching any of these names.
+    Chip { names: Vec<String> },
+    /// `label in0 "Vcore"`
+    Label { feature: String, label: String },
+    /// `compute in3 @*2, @/2`: converts raw values to real ones and back.
+    Compute { feature: String, from_raw: Expr, to_raw: Expr },
+    /// `set in0_min 1.2`
+    Set { subfeature: String, value: Expr },
+    /// `ignore fan3`
+    Ignore { feature: String },
+}
+impl Statement {
+    /// Whether this statement belongs to the preceding `chip` statement.
+    pub fn is_chip_scoped(&self) -> bool {
+        !matches!(self, Self::Bus { .. } | Self::Chip { .. })
+    }
+
+    /// The feature (or, for `set`, subfeature) this statement refers to, if any.
+    pub fn target(&self) -> Option<&str> {
+        match self {
+            Self::Label { feature, .. } | Self::Compute { feature, .. } | Self::Ignore { feature } => Some(feature),
+            Self::Set { subfeature, .. } => Some(subfeature),
+            Self::Bus { .. } | Self::Chip { .. } => None,
+        }
+    }
+
+    /// The keyword introducing this statement.
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            Self::Bus { .. } => "bus",
+            Self::Chip { .. } => "chip",
+            Self::Label { .. } => "label",
+            Self::Compute { .. } => "compute",
+            Self::Set { .. } => "set",
+            Self::Ignore { .. } => "ignore",
+        }
+    }
+
+    /// Parses the names of a `chip` statement into patterns. None for any other statement.
+    pub fn chip_patterns(&self) -> Option<Result<Vec<ChipPattern>>> {
+        match self {
+            Self::Chip { names } => Some(names.iter().map(|name| name.parse()).collect()),
+            _ => None,
+        }
+    }
+}
+
+/// An arithmetic expression, as used by `compute` and `set`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    /// `@`: the value being converted.
+    Raw,
+    /// The value of another subfeature of the same chip (e.g. `in0_input`).
+    Feature(String),
+    /// `-x`
+    Neg(Box<Expr>),
+    /// `^x`: e to the power of x.
+    Exp(Box<Expr>),
+    /// `` `x ``: the natural logarithm of x.
+    Ln(Box<Expr>),
+    Binary(Box<Expr>, BinOp, Box<Expr>),
+}
+impl Expr {
+    pub fn binary(left: Expr, op: BinOp, right: Expr) -> Self {
+        Self::Binary(Box::new(left), op, Box::new(right))
+    }
+
+    /// Evaluates this expression like libsensors does, with `raw` as the value of `@`.
+    ///
+    /// `feature` provides the values of other subfeatures, or the error to fail with
+    /// (libsensors uses [`ErrorKind::NoEntry`] for unknown ones).
+    /// Dividing by zero fails with [`ErrorKind::DivZero`].
+    pub fn eval<E: From<ErrorKind>>(&self, raw: f64, feature: &mut impl FnMut(&str) -> StdResult<f64, E>) -> StdResult<f64, E> {
+        Ok(match self {
+            Self::Number(value) => *value,
+            Self::Raw => raw,
+            Self::Feature(name) => feature(name)?,
+            Self::Neg(x) => -x.eval(raw, feature)?,
+            Self::Exp(x) => x.eval(raw, feature)?.exp(),
+            Self::Ln(x) => x.eval(raw, feature)?.ln(),
+            Self::Binary(left, op, right) => {
+                let (left, right) = (left.eval(raw, feature)?, right.eval(raw, feature)?);
+                match op {
+                    BinOp::Add => left + right,
+                    BinOp::Sub => left - right,
+                    BinOp::Mul => left * right,
+                    BinOp::Div if right == 0.0 => return Err(ErrorKind::DivZero.into()),
+                    BinOp::Div => left / right,
+                }
+            }
+        })
+    }
+
+    /// The names of the subfeatures this expression refers to, in order of appearance.
+    pub fn features(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        self.collect_features(&mut names);
+        names
+    }
+
+    fn collect_features<'a>(&'a self, names: &mut Vec<&'a str>) {
+        match self {
+            Self::Number(_) | Self::Raw => {}
+            Self::Feature(name) => names.push(name),
+            Self::Neg(x) | Self::Exp(x) | Self::Ln(x) => x.collect_features(names),
+            Self::Binary(left, _, right) => {
+                left.collect_features(names);
+                right.collect_features(names);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+impl BinOp {
+    pub fn as_char(self) -> char {
+        match self {
+            Self::Add => '+',
+            Self::Sub => '-',
+            Self::Mul => '*',
+            Self::Div => '/',
+        }
+    }
+
+    /// Binding strength; higher binds tighter.
+    pub(crate) fn precedence(self) -> u8 {
+        match self {
+            Self::Add | Self::Sub => 1,
+            Self::Mul | Self::Div => 2,
+        }
+    }
+}
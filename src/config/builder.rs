@@ -0,0 +1,171 @@
+use crate::ChipName;
+
+use super::{Config, Expr, ParseError, Span, Statement, parser::{is_bus_name, parse_expr}};
+
+/// Builds a [`Config`] in code, one chip block at a time.
+///
+/// For example, `ConfigBuilder::chip("nct6775-isa-*").label("temp1", "CPU").ignore("fan5").build()`
+/// gives the same configuration as parsing a `chip` statement followed by a `label` and an `ignore` statement.
+/// The result can be passed to [`LibSensors::init_with_source`](crate::LibSensors::init_with_source).
+///
+/// Arguments are checked as they are added, but errors are only returned by [`Self::build`],
+/// so that calls can be chained.
+#[derive(Debug, Clone)]
+pub struct ConfigBuilder {
+    config: Config,
+    /// The first invalid argument.
+    error: Option<ParseError>,
+}
+impl ConfigBuilder {
+    /// Starts a configuration with a block for chips matching `name` (e.g. `nct6775-isa-*`).
+    pub fn chip(name: impl Into<String>) -> Self {
+        Self::chips([name])
+    }
+
+    /// Starts a configuration with a block for chips matching any of `names`.
+    pub fn chips<S: Into<String>>(names: impl IntoIterator<Item = S>) -> Self {
+        Self { config: Config::default(), error: None }.then_chips(names)
+    }
+
+    /// Starts another block, for chips matching `name`.
+    pub fn then_chip(self, name: impl Into<String>) -> Self {
+        self.then_chips([name])
+    }
+
+    /// Starts another block, for chips matching any of `names`.
+    pub fn then_chips<S: Into<String>>(self, names: impl IntoIterator<Item = S>) -> Self {
+        let names: Vec<String> = names.into_iter().map(Into::into).collect();
+        self.push(|| {
+            if names.is_empty() {
+                return Err("chip statement without chip names".to_owned())
+            }
+            if let Some(name) = names.iter().find(|name| name.parse::<ChipName>().is_err()) {
+                return Err(format!("Parse error in chip name `{name}`"))
+            }
+            Ok(Statement::Chip { names })
+        })
+    }
+
+    /// Names the adapter of an I2C bus (`i2c-N`), so that chip names can refer to it regardless of its number.
+    pub fn bus(self, bus: impl Into<String>, adapter: impl Into<String>) -> Self {
+        let (bus, adapter) = (bus.into(), adapter.into());
+        self.push(|| {
+            if !is_bus_name(&bus) {
+                return Err(format!("Parse error in bus id `{bus}`"))
+            }
+            Ok(Statement::Bus { bus, adapter })
+        })
+    }
+
+    pub fn label(self, feature: impl Into<String>, label: impl Into<String>) -> Self {
+        self.push(|| Ok(Statement::Label { feature: feature.into(), label: label.into() }))
+    }
+
+    /// Converts the values of `feature` with the expressions `from_raw` (e.g. `@*2`) and `to_raw` (e.g. `@/2`).
+    pub fn compute(self, feature: impl Into<String>, from_raw: impl AsRef<str>, to_raw: impl AsRef<str>) -> Self {
+        let feature = feature.into();
+        self.push(|| {
+            let from_raw = expr(&format!("compute {feature}"), from_raw.as_ref())?;
+            let to_raw = expr(&format!("compute {feature}"), to_raw.as_ref())?;
+            Ok(Statement::Compute { feature, from_raw, to_raw })
+        })
+    }
+
+    /// Sets `subfeature` (e.g. `in0_min`) to the expression `value` (e.g. `3.3*0.95`) when the sets are applied.
+    pub fn set(self, subfeature: impl Into<String>, value: impl AsRef<str>) -> Self {
+        let subfeature = subfeature.into();
+        self.push(|| {
+            let value = expr(&format!("set {subfeature}"), value.as_ref())?;
+            Ok(Statement::Set { subfeature, value })
+        })
+    }
+
+    pub fn ignore(self, feature: impl Into<String>) -> Self {
+        self.push(|| Ok(Statement::Ignore { feature: feature.into() }))
+    }
+
+    /// Returns the configuration, or the first invalid argument.
+    ///
+    /// The line of the error is the one the offending statement would have had in the written configuration.
+    pub fn build(self) -> Result<Config, ParseError> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.config),
+        }
+    }
+
+    /// Appends the statement made by `make`, unless an earlier argument was invalid.
+    fn push(mut self, make: impl FnOnce() -> Result<Statement, String>) -> Self {
+        if self.error.is_none() {
+            match make() {
+                Ok(statement) => self.config.push(statement),
+                Err(message) => {
+                    let line = self.config.lines.len() as u32 + 1;
+                    self.error = Some(ParseError::new(message, Span { line, ..Span::default() }));
+                }
+            }
+        }
+        self
+    }
+}
+
+fn expr(context: &str, src: &str) -> Result<Expr, String> {
+    parse_expr(src).map_err(|e| format!("{context}: invalid expression `{src}`: {}", e.message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_round_trips_through_parse() {
+        let config = ConfigBuilder::chip("nct6775-isa-*")
+            .label("temp1", "CPU \"package\"")
+            .compute("in3", "@*(1+6.8/10)", "@/(1+6.8/10)")
+            .set("in0_min", "3.3*0.95")
+            .ignore("fan5")
+            .bus("i2c-0", "SMBus I801 adapter at 0400")
+            .then_chips(["lm78-i2c-0-2d", "lm79-*"])
+            .set("in1_max", "in1_input*-1")
+            .build()
+            .unwrap();
+        let text = config.to_string();
+        assert_eq!(text, r#"chip "nct6775-isa-*"
+    label temp1 "CPU \"package\""
+    compute in3 @*(1+6.8/10), @/(1+6.8/10)
+    set in0_min 3.3*0.95
+    ignore fan5
+bus "i2c-0" "SMBus I801 adapter at 0400"
+chip "lm78-i2c-0-2d" "lm79-*"
+    set in1_max in1_input*-1
+"#);
+        assert_eq!(Config::parse(&text).unwrap(), config);
+    }
+
+    #[test]
+    fn first_error_is_returned() {
+        let error = ConfigBuilder::chip("nct6775-isa-*")
+            .label("temp1", "CPU")
+            .set("in0_min", "3.3*")
+            .then_chip("not a chip name")
+            .build()
+            .unwrap_err();
+        assert_eq!(error.span.line, 3);
+        assert!(error.message.starts_with("set in0_min: invalid expression `3.3*`"), "{}", error.message);
+    }
+
+    #[test]
+    fn invalid_arguments_are_errors() {
+        let errors = [
+            ConfigBuilder::chip("coretemp").build(),
+            ConfigBuilder::chips(Vec::<String>::new()).build(),
+            ConfigBuilder::chip("coretemp-*").bus("isa-0", "ISA adapter").build(),
+            ConfigBuilder::chip("coretemp-*").compute("temp1", "@*2", "@/").build(),
+        ];
+        let messages: Vec<_> = errors.into_iter().map(|result| result.unwrap_err().message).collect();
+        assert_eq!(messages[0], "Parse error in chip name `coretemp`");
+        assert_eq!(messages[1], "chip statement without chip names");
+        assert_eq!(messages[2], "Parse error in bus id `isa-0`");
+        assert!(messages[3].starts_with("compute temp1: invalid expression `@/`"), "{}", messages[3]);
+    }
+}
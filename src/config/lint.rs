@@ -0,0 +1,247 @@
+use std::{fmt::Display, path::{Path, PathBuf}};
+
+use crate::{BusType, ChipInfo, ChipName, error::ErrorKind};
+
+use super::{Config, Expr, Span, Statement, resolve_chip_name};
+
+/// Raw values a `compute` statement is converted at to check that its expressions undo each other.
+const SAMPLES: [f64; 6] = [0.0, 0.5, 1.0, 2.0, 10.0, 100.0];
+/// How far a converted value may drift relative to its magnitude before it counts as different.
+const TOLERANCE: f64 = 1e-6;
+
+/// A problem found by [`Config::lint`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// The file the configuration was read from, if it was given.
+    pub file: Option<PathBuf>,
+    /// Where the offending argument is, or the default span (with line 0) if the statement was not parsed from text.
+    pub span: Span,
+    pub kind: DiagnosticKind,
+}
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{}:{}: {}", file.display(), self.span.line, self.kind),
+            None => write!(f, "line {}: {}", self.span.line, self.kind),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiagnosticKind {
+    /// A name of a `chip` statement matches none of the chips.
+    UnmatchedChip { pattern: String },
+    /// A `label`, `compute` or `ignore` statement refers to a feature a matched chip does not have.
+    UnknownFeature { keyword: &'static str, chip: ChipName, feature: String },
+    /// A `set` statement, or an expression, refers to a subfeature a matched chip does not have.
+    UnknownSubfeature { keyword: &'static str, chip: ChipName, subfeature: String },
+    /// Converting `raw` with the first expression of a `compute` statement and back with the second
+    /// gives `roundtrip` instead.
+    InconsistentCompute { feature: String, raw: f64, roundtrip: f64 },
+}
+impl Display for DiagnosticKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnmatchedChip { pattern } => write!(f, "chip `{pattern}` matches no detected chip"),
+            Self::UnknownFeature { keyword, chip, feature } =>
+                write!(f, "{keyword} refers to feature `{feature}`, which chip {chip} does not have"),
+            Self::UnknownSubfeature { keyword, chip, subfeature } =>
+                write!(f, "{keyword} refers to subfeature `{subfeature}`, which chip {chip} does not have"),
+            Self::InconsistentCompute { feature, raw, roundtrip } =>
+                write!(f, "compute expressions of `{feature}` are not inverse: {raw} converts back to {roundtrip}"),
+        }
+    }
+}
+
+pub(super) fn lint(config: &Config, file: Option<&Path>, chips: &[ChipInfo]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let buses: Vec<_> = config.buses().collect();
+    let adapter_bus = |adapter: &str| chips.iter()
+        .find(|chip| chip.bus.type_ == BusType::I2C && chip.adapter.as_deref() == Some(adapter))
+        .map(|chip| chip.bus.nr);
+    // The chips matched by the current chip block.
+    let mut matched: Vec<&ChipInfo> = Vec::new();
+    for line in &config.lines {
+        let Some(statement) = &line.statement else { continue };
+        let at = |index| line.arg_span(index).unwrap_or(line.span);
+        let mut report = |span, kind| diagnostics.push(Diagnostic { file: file.map(Path::to_owned), span, kind });
+        match statement {
+            Statement::Bus { .. } => {}
+            Statement::Chip { names } => {
+                matched.clear();
+                for (index, name) in names.iter().enumerate() {
+                    // libsensors ignores names that do not parse or refer to an unknown bus, so they match nothing.
+                    let found: Vec<_> = match resolve_chip_name(name, &buses, adapter_bus) {
+                        Some(pattern) => chips.iter().filter(|chip| pattern.matches(&chip.name)).collect(),
+                        None => Vec::new(),
+                    };
+                    if found.is_empty() {
+                        report(at(index), DiagnosticKind::UnmatchedChip { pattern: name.clone() });
+                    }
+                    for chip in found {
+                        if !matched.iter().any(|m| m.name == chip.name) {
+                            matched.push(chip);
+                        }
+                    }
+                }
+            }
+            Statement::Label { feature, .. } | Statement::Ignore { feature } => {
+                for chip in &matched {
+                    check_feature(chip, statement.keyword(), feature, &mut |kind| report(at(0), kind));
+                }
+            }
+            Statement::Compute { feature, from_raw, to_raw } => {
+                for chip in &matched {
+                    check_feature(chip, "compute", feature, &mut |kind| report(at(0), kind));
+                    for (index, expr) in [(1, from_raw), (2, to_raw)] {
+                        for name in expr.features() {
+                            check_subfeature(chip, "compute", name, &mut |kind| report(at(index), kind));
+                        }
+                    }
+                }
+                if let Some((raw, roundtrip)) = inconsistency(from_raw, to_raw) {
+                    let span = Span { end: at(2).end, ..at(1) };
+                    report(span, DiagnosticKind::InconsistentCompute { feature: feature.clone(), raw, roundtrip });
+                }
+            }
+            Statement::Set { subfeature, value } => {
+                for chip in &matched {
+                    check_subfeature(chip, "set", subfeature, &mut |kind| report(at(0), kind));
+                    for name in value.features() {
+                        check_subfeature(chip, "set", name, &mut |kind| report(at(1), kind));
+                    }
+                }
+            }
+        }
+    }
+    diagnostics
+}
+
+fn check_feature(chip: &ChipInfo, keyword: &'static str, feature: &str, report: &mut impl FnMut(DiagnosticKind)) {
+    if chip.feature(feature).is_none() {
+        report(DiagnosticKind::UnknownFeature { keyword, chip: chip.name.clone(), feature: feature.to_owned() });
+    }
+}
+
+fn check_subfeature(chip: &ChipInfo, keyword: &'static str, subfeature: &str, report: &mut impl FnMut(DiagnosticKind)) {
+    if !chip.features.iter().any(|feature| feature.subfeature(subfeature).is_some()) {
+        report(DiagnosticKind::UnknownSubfeature { keyword, chip: chip.name.clone(), subfeature: subfeature.to_owned() });
+    }
+}
+
+/// Finds a sample that does not survive being converted by `from_raw` and back by `to_raw`.
+///
+/// Expressions referring to other subfeatures depend on their values and are not checked.
+/// Samples outside the domain of either expression (e.g. the logarithm of 0, or dividing by 0) are skipped.
+fn inconsistency(from_raw: &Expr, to_raw: &Expr) -> Option<(f64, f64)> {
+    if !from_raw.features().is_empty() || !to_raw.features().is_empty() {
+        return None
+    }
+    let mut no_features = |_: &str| Err(ErrorKind::NoEntry);
+    SAMPLES.into_iter().find_map(|raw| {
+        let value = from_raw.eval(raw, &mut no_features).ok().filter(|v| v.is_finite())?;
+        let roundtrip = to_raw.eval(value, &mut no_features).ok().filter(|v| v.is_finite())?;
+        ((roundtrip - raw).abs() > TOLERANCE * raw.abs().max(1.0)).then_some((raw, roundtrip))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BusId, FeatureInfo, SubfeatureFlags, SubfeatureInfo, SubfeatureType, feature::FeatureType};
+
+    use super::*;
+
+    /// A chip with `in` features, each with the given subfeatures.
+    fn chip(name: &str, adapter: &str, features: &[(&str, &[&str])]) -> ChipInfo {
+        let name: ChipName = name.parse().unwrap();
+        let bus = BusId { type_: name.bus_type().unwrap(), nr: name.bus_nr().unwrap() };
+        let features = features.iter().enumerate().map(|(number, (feature, subfeatures))| FeatureInfo {
+            name: feature.to_string(),
+            number: number as _,
+            label: None,
+            type_: FeatureType::In,
+            subfeatures: subfeatures.iter().enumerate().map(|(number, subfeature)| SubfeatureInfo {
+                name: subfeature.to_string(),
+                number: number as _,
+                type_: SubfeatureType::InInput,
+                flags: SubfeatureFlags::READ,
+                value: None,
+            }).collect(),
+            errors: Vec::new(),
+        }).collect();
+        ChipInfo {
+            address: name.address().unwrap(),
+            name,
+            adapter: Some(adapter.to_owned()),
+            bus,
+            sysfs_path: None,
+            features,
+            errors: Vec::new(),
+        }
+    }
+
+    fn chips() -> Vec<ChipInfo> {
+        vec![
+            chip("coretemp-isa-0000", "ISA adapter", &[("temp1", &["temp1_input", "temp1_max"])]),
+            chip("w83781d-i2c-5-2d", "SMBus I801 adapter", &[("in0", &["in0_input", "in0_min"])]),
+        ]
+    }
+
+    fn lint(src: &str) -> Vec<(u32, DiagnosticKind)> {
+        let config = Config::parse(src).unwrap();
+        config.lint(None, &chips()).into_iter().map(|d| (d.span.line, d.kind)).collect()
+    }
+
+    #[test]
+    fn clean_config_has_no_diagnostics() {
+        let src = "chip \"coretemp-isa-*\"\n    label temp1 \"CPU\"\n    set temp1_max 80\n\
+                   chip \"w83781d-*\"\n    compute in0 @*2, @/2\n    set in0_min in0_input*0.9\n";
+        assert_eq!(lint(src), []);
+    }
+
+    #[test]
+    fn unmatched_chip() {
+        let src = "chip \"coretemp-isa-*\" \"it87-*\"\n    ignore temp1\n";
+        assert_eq!(lint(src), [(1, DiagnosticKind::UnmatchedChip { pattern: "it87-*".to_owned() })]);
+
+        let config = Config::parse(src).unwrap();
+        let diagnostic = &config.lint(Some(Path::new("test.conf")), &chips())[0];
+        assert_eq!(&src[diagnostic.span.start..diagnostic.span.end], "\"it87-*\"");
+        assert_eq!(diagnostic.to_string(), "test.conf:1: chip `it87-*` matches no detected chip");
+    }
+
+    #[test]
+    fn unknown_feature() {
+        let coretemp: ChipName = "coretemp-isa-0000".parse().unwrap();
+        let src = "chip \"coretemp-*\"\n    label temp2 \"GPU\"\n    set temp1_min 10\n    compute temp1 @+in0_input, @-in0_input\n";
+        assert_eq!(lint(src), [
+            (2, DiagnosticKind::UnknownFeature { keyword: "label", chip: coretemp.clone(), feature: "temp2".to_owned() }),
+            (3, DiagnosticKind::UnknownSubfeature { keyword: "set", chip: coretemp.clone(), subfeature: "temp1_min".to_owned() }),
+            (4, DiagnosticKind::UnknownSubfeature { keyword: "compute", chip: coretemp.clone(), subfeature: "in0_input".to_owned() }),
+            (4, DiagnosticKind::UnknownSubfeature { keyword: "compute", chip: coretemp, subfeature: "in0_input".to_owned() }),
+        ]);
+    }
+
+    #[test]
+    fn inconsistent_compute() {
+        let src = "chip \"w83781d-*\"\n    compute in0 @*2, @/3\n    compute in0 ^@, `@\n";
+        assert_eq!(lint(src), [
+            (2, DiagnosticKind::InconsistentCompute { feature: "in0".to_owned(), raw: 0.5, roundtrip: 1.0 / 3.0 }),
+        ]);
+    }
+
+    #[test]
+    fn bus_statements_remap_chip_names() {
+        // The configuration was written for a board where the adapter was i2c-0; here it is i2c-5.
+        let src = "bus \"i2c-0\" \"SMBus I801 adapter\"\nchip \"w83781d-i2c-0-2d\"\n    label in0 \"Vcore\"\n";
+        assert_eq!(lint(src), []);
+
+        // Without a bus statement, or with one naming another adapter, libsensors ignores the name.
+        let unmatched = |pattern: &str| (2, DiagnosticKind::UnmatchedChip { pattern: pattern.to_owned() });
+        assert_eq!(lint("chip \"coretemp-*\"\nchip \"w83781d-i2c-5-2d\"\n"), [unmatched("w83781d-i2c-5-2d")]);
+        assert_eq!(
+            lint("bus \"i2c-5\" \"nVidia i2c adapter\"\nchip \"w83781d-i2c-5-2d\"\n"),
+            [unmatched("w83781d-i2c-5-2d")],
+        );
+    }
+}
@@ -0,0 +1,598 @@
+use std::fmt::Display;
+
+use std::ffi::c_short;
+
+use super::{BinOp, Config, Expr, Line, Span, Statement, ast::Source};
+use crate::ChipName;
+
+/// An error in the syntax of a configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    pub span: Span,
+}
+impl ParseError {
+    pub(super) fn new(message: impl Into<String>, span: Span) -> Self {
+        Self { message: message.into(), span }
+    }
+}
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.span.line, self.message)
+    }
+}
+impl std::error::Error for ParseError { }
+
+type Result<T> = std::result::Result<T, ParseError>;
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    /// A bare word (e.g. `in0`).
+    Word(String),
+    /// A quoted string, with escapes resolved.
+    Str(String),
+    Number(f64),
+    Punct(char),
+    /// A comment, without the leading `#`.
+    Comment(String),
+    Newline,
+}
+impl Display for TokenKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Word(word) => write!(f, "`{word}`"),
+            Self::Str(s) => write!(f, "{s:?}"),
+            Self::Number(n) => write!(f, "`{n}`"),
+            Self::Punct(c) => write!(f, "`{c}`"),
+            Self::Comment(_) => write!(f, "comment"),
+            Self::Newline => write!(f, "end of line"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    span: Span,
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Whether `s` can be written without quotes.
+pub(super) fn is_word(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(is_word_char) && !s.starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Whether `s` is an I2C bus name (`i2c-N`), the only kind libsensors accepts in `bus` statements.
+pub(super) fn is_bus_name(s: &str) -> bool {
+    s.strip_prefix("i2c-")
+        .and_then(|nr| nr.parse::<c_short>().ok())
+        .is_some_and(|nr| nr >= 0)
+}
+
+struct Lexer<'a> {
+    src: &'a str,
+    pos: usize,
+    line: u32,
+}
+impl<'a> Lexer<'a> {
+    fn new(src: &'a str) -> Self {
+        Self { src, pos: 0, line: 1 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn span_from(&self, start: usize, line: u32) -> Span {
+        Span { start, end: self.pos, line }
+    }
+
+    /// Skips blanks and line continuations (a `\` at the end of a line).
+    fn skip_blanks(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start_matches([' ', '\t', '\r']);
+            self.pos += rest.len() - trimmed.len();
+            if let Some(after) = trimmed.strip_prefix('\\') {
+                let after_blanks = after.trim_start_matches([' ', '\t', '\r']);
+                if after_blanks.starts_with('\n') {
+                    self.pos += trimmed.len() - after_blanks.len() + 1;
+                    self.line += 1;
+                    continue
+                }
+            }
+            break
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Option<Token>> {
+        self.skip_blanks();
+        let (start, line) = (self.pos, self.line);
+        let Some(c) = self.peek() else {
+            return Ok(None)
+        };
+        let kind = match c {
+            '\n' => {
+                self.pos += 1;
+                self.line += 1;
+                TokenKind::Newline
+            },
+            '#' => {
+                let text = self.rest()[1..].split('\n').next().unwrap_or_default();
+                self.pos += 1 + text.len();
+                TokenKind::Comment(text.to_owned())
+            },
+            '"' => TokenKind::Str(self.string()?),
+            c if is_word_char(c) || c == '.' => {
+                let word = self.rest().split(|c: char| !is_word_char(c) && c != '.').next().unwrap_or_default();
+                self.pos += word.len();
+                if word.starts_with(|c: char| c.is_ascii_digit() || c == '.') && word.chars().all(|c| c.is_ascii_digit() || c == '.') {
+                    let number = word.parse()
+                        .map_err(|_| ParseError::new(format!("invalid number `{word}`"), self.span_from(start, line)))?;
+                    TokenKind::Number(number)
+                } else if word.contains('.') {
+                    return Err(ParseError::new(format!("invalid name `{word}`"), self.span_from(start, line)))
+                } else {
+                    TokenKind::Word(word.to_owned())
+                }
+            },
+            '+' | '-' | '*' | '/' | '(' | ')' | ',' | '@' | '^' | '`' => {
+                self.pos += 1;
+                TokenKind::Punct(c)
+            },
+            c => {
+                self.pos += c.len_utf8();
+                return Err(ParseError::new(format!("unexpected character `{c}`"), self.span_from(start, line)))
+            },
+        };
+        Ok(Some(Token { kind, span: self.span_from(start, line) }))
+    }
+
+    /// Reads a quoted string, starting at the opening quote.
+    fn string(&mut self) -> Result<String> {
+        let (start, line) = (self.pos, self.line);
+        self.pos += 1;
+        let mut s = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok(s)
+                },
+                // The same escapes as the libsensors lexer.
+                '\\' => match chars.next() {
+                    Some((_, 'a')) => s.push('\x07'),
+                    Some((_, 'b')) => s.push('\x08'),
+                    Some((_, 'f')) => s.push('\x0c'),
+                    Some((_, 'n')) => s.push('\n'),
+                    Some((_, 'r')) => s.push('\r'),
+                    Some((_, 't')) => s.push('\t'),
+                    Some((_, 'v')) => s.push('\x0b'),
+                    Some((_, '\n')) | None => break,
+                    Some((_, c)) => s.push(c),
+                },
+                '\n' => break,
+                c => s.push(c),
+            }
+        }
+        Err(ParseError::new("unterminated string", Span { start, end: start + 1, line }))
+    }
+}
+
+/// The tokens of one statement.
+struct Tokens {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Where errors about missing tokens are reported.
+    end: Span,
+    /// The spans of the arguments parsed by [`Self::arg`] so far.
+    args: Vec<Span>,
+}
+impl Tokens {
+    fn new(tokens: Vec<Token>, end: Span) -> Self {
+        Self { tokens, pos: 0, end, args: Vec::new() }
+    }
+
+    /// Parses a statement argument with `parse`, recording the span of the tokens it took.
+    fn arg<T>(&mut self, parse: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        let start = self.pos;
+        let value = parse(self)?;
+        if let Some([first, .., last] | [first @ last]) = self.tokens.get(start..self.pos) {
+            self.args.push(Span { end: last.span.end, ..first.span });
+        }
+        Ok(value)
+    }
+
+    fn peek(&self) -> Option<&TokenKind> {
+        self.tokens.get(self.pos).map(|token| &token.kind)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+        token
+    }
+
+    fn error_here(&self, message: impl Into<String>) -> ParseError {
+        let span = self.tokens.get(self.pos).map_or(self.end, |token| token.span);
+        ParseError::new(message, span)
+    }
+
+    fn expected(&self, what: &str) -> ParseError {
+        match self.peek() {
+            Some(found) => self.error_here(format!("expected {what}, found {found}")),
+            None => self.error_here(format!("expected {what}, found end of line")),
+        }
+    }
+
+    /// A name, which can be a bare word or a quoted string.
+    fn name(&mut self, what: &str) -> Result<String> {
+        match self.peek() {
+            Some(TokenKind::Word(s) | TokenKind::Str(s)) => {
+                let s = s.clone();
+                self.pos += 1;
+                Ok(s)
+            },
+            _ => Err(self.expected(what)),
+        }
+    }
+
+    /// A chip name, which must be valid like for libsensors.
+    fn chip_name(&mut self) -> Result<String> {
+        let span = self.tokens.get(self.pos).map_or(self.end, |token| token.span);
+        let name = self.name("a chip name")?;
+        name.parse::<ChipName>()
+            .map_err(|_| ParseError::new("Parse error in chip name", span))?;
+        Ok(name)
+    }
+
+    /// An I2C bus name (`i2c-N`).
+    fn bus_name(&mut self) -> Result<String> {
+        let span = self.tokens.get(self.pos).map_or(self.end, |token| token.span);
+        let name = self.name("a bus name")?;
+        if !is_bus_name(&name) {
+            return Err(ParseError::new("Parse error in bus id", span))
+        }
+        Ok(name)
+    }
+
+    fn punct(&mut self, c: char) -> Result<()> {
+        match self.peek() {
+            Some(TokenKind::Punct(p)) if *p == c => {
+                self.pos += 1;
+                Ok(())
+            },
+            _ => Err(self.expected(&format!("`{c}`"))),
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr> {
+        let mut left = self.term()?;
+        loop {
+            let op = match self.peek() {
+                Some(TokenKind::Punct('+')) => BinOp::Add,
+                Some(TokenKind::Punct('-')) => BinOp::Sub,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            left = Expr::binary(left, op, self.term()?);
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr> {
+        let mut left = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Some(TokenKind::Punct('*')) => BinOp::Mul,
+                Some(TokenKind::Punct('/')) => BinOp::Div,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            left = Expr::binary(left, op, self.unary()?);
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        let op: fn(Box<Expr>) -> Expr = match self.peek() {
+            Some(TokenKind::Punct('-')) => Expr::Neg,
+            Some(TokenKind::Punct('^')) => Expr::Exp,
+            Some(TokenKind::Punct('`')) => Expr::Ln,
+            _ => return self.primary(),
+        };
+        self.pos += 1;
+        Ok(op(Box::new(self.unary()?)))
+    }
+
+    fn primary(&mut self) -> Result<Expr> {
+        let expr = match self.peek() {
+            Some(TokenKind::Number(n)) => Expr::Number(*n),
+            Some(TokenKind::Punct('@')) => Expr::Raw,
+            Some(TokenKind::Word(name) | TokenKind::Str(name)) => Expr::Feature(name.clone()),
+            Some(TokenKind::Punct('(')) => {
+                self.pos += 1;
+                let expr = self.expr()?;
+                self.punct(')')?;
+                return Ok(expr)
+            },
+            _ => return Err(self.expected("an expression")),
+        };
+        self.pos += 1;
+        Ok(expr)
+    }
+
+    fn statement(&mut self) -> Result<Statement> {
+        let keyword = match self.next().map(|token| &token.kind) {
+            Some(TokenKind::Word(keyword)) => keyword.clone(),
+            _ => {
+                self.pos -= 1;
+                return Err(self.expected("a statement"))
+            },
+        };
+        let statement = match keyword.as_str() {
+            "bus" => Statement::Bus {
+                bus: self.arg(Self::bus_name)?,
+                adapter: self.arg(|t| t.name("an adapter name"))?,
+            },
+            "chip" => {
+                let mut names = vec![self.arg(Self::chip_name)?];
+                while matches!(self.peek(), Some(TokenKind::Word(_) | TokenKind::Str(_))) {
+                    names.push(self.arg(Self::chip_name)?);
+                }
+                Statement::Chip { names }
+            },
+            "label" => Statement::Label {
+                feature: self.arg(|t| t.name("a feature name"))?,
+                label: self.arg(|t| t.name("a label"))?,
+            },
+            "compute" => {
+                let feature = self.arg(|t| t.name("a feature name"))?;
+                let from_raw = self.arg(Self::expr)?;
+                self.punct(',')?;
+                Statement::Compute { feature, from_raw, to_raw: self.arg(Self::expr)? }
+            },
+            "set" => Statement::Set {
+                subfeature: self.arg(|t| t.name("a subfeature name"))?,
+                value: self.arg(Self::expr)?,
+            },
+            "ignore" => Statement::Ignore { feature: self.arg(|t| t.name("a feature name"))? },
+            _ => {
+                self.pos -= 1;
+                return Err(self.error_here(format!("unknown statement `{keyword}`")))
+            },
+        };
+        if self.pos < self.tokens.len() {
+            return Err(self.expected("end of line"))
+        }
+        Ok(statement)
+    }
+}
+
+/// The messages libsensors uses for statements outside of a chip block.
+fn before_chip_message(statement: &Statement) -> String {
+    let keyword = statement.keyword();
+    let mut chars = keyword.chars();
+    let capitalised: String = chars.next().into_iter().flat_map(char::to_uppercase).chain(chars).collect();
+    format!("{capitalised} statement before first chip statement")
+}
+
+/// Parses a lone expression (e.g. `@*2`), as found in `compute` and `set` statements.
+pub(super) fn parse_expr(src: &str) -> Result<Expr> {
+    let mut lexer = Lexer::new(src);
+    let mut tokens = Vec::new();
+    while let Some(token) = lexer.next_token()? {
+        if matches!(token.kind, TokenKind::Newline | TokenKind::Comment(_)) {
+            return Err(ParseError::new(format!("expected an expression, found {}", token.kind), token.span))
+        }
+        tokens.push(token);
+    }
+    let end = Span { start: src.len(), end: src.len(), line: lexer.line };
+    let mut tokens = Tokens::new(tokens, end);
+    let expr = tokens.expr()?;
+    if tokens.pos < tokens.tokens.len() {
+        return Err(tokens.expected("end of expression"))
+    }
+    Ok(expr)
+}
+
+pub(super) fn parse(src: &str) -> Result<Config> {
+    let mut lexer = Lexer::new(src);
+    let mut lines = Vec::new();
+    let mut seen_chip = false;
+    loop {
+        let (line_start, line) = (lexer.pos, lexer.line);
+        if line_start == src.len() {
+            break
+        }
+        let indent: String = src[line_start..].chars().take_while(|c| matches!(c, ' ' | '\t')).collect();
+        let text_start = line_start + indent.len();
+
+        let mut tokens = Vec::new();
+        let mut comment = None;
+        let mut text_end = src.len();
+        while let Some(token) = lexer.next_token()? {
+            match token.kind {
+                TokenKind::Newline => {
+                    text_end = token.span.start;
+                    break
+                },
+                TokenKind::Comment(text) => comment = Some((text, token.span)),
+                _ => tokens.push(token),
+            }
+        }
+
+        let first = tokens.first().map(|token| token.span).or(comment.as_ref().map(|(_, span)| *span));
+        let last = comment.as_ref().map(|(_, span)| *span).or(tokens.last().map(|token| token.span));
+        let span = match (first, last) {
+            (Some(first), Some(last)) => Span { start: first.start, end: last.end, line: first.line },
+            _ => Span { start: line_start, end: line_start, line },
+        };
+
+        let (statement, args) = if tokens.is_empty() {
+            (None, Vec::new())
+        } else {
+            let end = tokens.last().map_or(span, |token| Span { start: token.span.end, ..token.span });
+            let mut tokens = Tokens::new(tokens, end);
+            let statement = tokens.statement()?;
+            if statement.is_chip_scoped() && !seen_chip {
+                return Err(ParseError::new(before_chip_message(&statement), span))
+            }
+            seen_chip |= matches!(statement, Statement::Chip { .. });
+            (Some(statement), tokens.args)
+        };
+        let comment = comment.map(|(text, _)| text);
+        let source = Source {
+            statement: statement.clone(),
+            comment: comment.clone(),
+            text: src[text_start..text_end].to_owned(),
+            args,
+        };
+        lines.push(Line { indent, statement, comment, span, source: Some(source) });
+    }
+    Ok(Config { lines })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"# Sample configuration
+bus "i2c-0" "SMBus I801 adapter at 0400"
+
+chip "lm78-*" "lm79-*"   # two names
+    label in0 "VCore 1"
+	label temp1 "CPU \"hot\" \\ spot"
+    compute in3 ((6.8/10)+1)*@ ,  @/((6.8/10)+1)
+    set in0_min  vid * 0.95 # odd spacing
+    set in1_max \
+        3.3 * 1.05
+    ignore fan3
+
+chip "coretemp-isa-*"
+    compute temp1 -@, -@
+"#;
+
+    fn num(n: f64) -> Expr {
+        Expr::Number(n)
+    }
+
+    fn neg(x: Expr) -> Expr {
+        Expr::Neg(Box::new(x))
+    }
+
+    /// The line whose statement has `keyword` and `target`.
+    fn find<'a>(config: &'a Config, keyword: &str, target: &str) -> &'a Line {
+        config.lines.iter()
+            .find(|line| line.statement.as_ref().is_some_and(|s| s.keyword() == keyword && s.target() == Some(target)))
+            .unwrap()
+    }
+
+    #[test]
+    fn round_trip_is_byte_identical() {
+        assert_eq!(Config::parse(SAMPLE).unwrap().to_string(), SAMPLE);
+    }
+
+    #[test]
+    fn line_continuation() {
+        let config = Config::parse(SAMPLE).unwrap();
+        let set = find(&config, "set", "in1_max");
+        assert_eq!(set.statement, Some(Statement::Set {
+            subfeature: "in1_max".to_owned(),
+            value: Expr::binary(num(3.3), BinOp::Mul, num(1.05)),
+        }));
+        assert_eq!(set.span.line, 9);
+        // Both physical lines make up one line, and the numbering carries on after them.
+        assert_eq!(find(&config, "ignore", "fan3").span.line, 11);
+    }
+
+    #[test]
+    fn string_escapes() {
+        let config = Config::parse(SAMPLE).unwrap();
+        let label = |line: &Line| match &line.statement {
+            Some(Statement::Label { label, .. }) => label.clone(),
+            other => panic!("{other:?}"),
+        };
+        assert_eq!(label(find(&config, "label", "temp1")), r#"CPU "hot" \ spot"#);
+
+        let config = Config::parse("chip \"lm78-*\"\n    label in0 \"a\\tb\\nc\\q\\a\\b\\f\\r\\v\"\n").unwrap();
+        assert_eq!(label(find(&config, "label", "in0")), "a\tb\ncq\x07\x08\x0c\r\x0b");
+    }
+
+    #[test]
+    fn precedence_and_unary_minus() {
+        let cases = [
+            ("1+2*3", Expr::binary(num(1.0), BinOp::Add, Expr::binary(num(2.0), BinOp::Mul, num(3.0)))),
+            ("2-3-4", Expr::binary(Expr::binary(num(2.0), BinOp::Sub, num(3.0)), BinOp::Sub, num(4.0))),
+            ("8/4/2", Expr::binary(Expr::binary(num(8.0), BinOp::Div, num(4.0)), BinOp::Div, num(2.0))),
+            ("-@*2", Expr::binary(neg(Expr::Raw), BinOp::Mul, num(2.0))),
+            ("2*-@", Expr::binary(num(2.0), BinOp::Mul, neg(Expr::Raw))),
+            ("--@", neg(neg(Expr::Raw))),
+            ("-(1+2)", neg(Expr::binary(num(1.0), BinOp::Add, num(2.0)))),
+            ("^`@", Expr::Exp(Box::new(Expr::Ln(Box::new(Expr::Raw))))),
+        ];
+        for (src, expected) in cases {
+            assert_eq!(parse_expr(src).unwrap(), expected, "{src}");
+        }
+    }
+
+    #[test]
+    fn statements_before_chip_are_rejected() {
+        for (src, message) in [
+            ("label temp1 \"CPU\"\n", "Label statement before first chip statement"),
+            ("# comment\n\ncompute in0 @*2, @/2\n", "Compute statement before first chip statement"),
+            ("set in0_min 1\n", "Set statement before first chip statement"),
+            ("ignore fan1\n", "Ignore statement before first chip statement"),
+        ] {
+            let error = Config::parse(src).unwrap_err();
+            assert_eq!(error.message, message);
+            assert_eq!(error.span.line, src.lines().count() as u32);
+        }
+        assert!(Config::parse("bus \"i2c-0\" \"SMBus I801 adapter\"\nchip \"lm78-i2c-0-2d\"\n").is_ok());
+    }
+
+    #[test]
+    fn error_positions() {
+        let at = |src: &str, needle: &str, len: usize| {
+            let start = src.rfind(needle).unwrap();
+            let line = src[..start].matches('\n').count() as u32 + 1;
+            Span { start, end: start + len, line }
+        };
+        let cases = [
+            // Missing tokens are reported right after the last one.
+            ("chip \"lm78-*\"\n    set in0_min 1 +\n", "expected an expression, found end of line", "\n", 0),
+            ("chip \"lm78-*\"\n    label temp1 $\n", "unexpected character `$`", "$", 1),
+            ("chip \"lm78-*\"\n\n    lable temp1 \"x\"\n", "unknown statement `lable`", "lable", 5),
+            ("chip \"lm78-*\"\n    compute in0 @*2 @/2\n", "expected `,`, found `@`", "@/2", 1),
+            ("chip \"lm78\"\n", "Parse error in chip name", "\"lm78\"", 6),
+            ("chip \"lm78-*\n", "unterminated string", "\"", 1),
+            ("bus \"i2c-x\" \"adapter\"\n", "Parse error in bus id", "\"i2c-x\"", 7),
+        ];
+        for (src, message, needle, len) in cases {
+            let error = Config::parse(src).unwrap_err();
+            assert_eq!(error.message, message, "{src:?}");
+            assert_eq!(error.span, at(src, needle, len), "{src:?}");
+        }
+    }
+
+    #[test]
+    fn argument_spans() {
+        let config = Config::parse(SAMPLE).unwrap();
+        let args = |line: &Line| (0..).map_while(|i| line.arg_span(i)).map(|span| &SAMPLE[span.start..span.end]).collect::<Vec<_>>();
+        let chip = config.lines.iter().find(|line| matches!(line.statement, Some(Statement::Chip { .. }))).unwrap();
+        assert_eq!(args(chip), ["\"lm78-*\"", "\"lm79-*\""]);
+        assert_eq!(args(find(&config, "compute", "in3")), ["in3", "((6.8/10)+1)*@", "@/((6.8/10)+1)"]);
+        assert_eq!(args(find(&config, "set", "in1_max")), ["in1_max", "3.3 * 1.05"]);
+
+        let mut line = find(&config, "set", "in0_min").clone();
+        assert_eq!(line.arg_span(1).map(|span| &SAMPLE[span.start..span.end]), Some("vid * 0.95"));
+        line.statement = Some(Statement::Set { subfeature: "in0_min".to_owned(), value: num(1.0) });
+        assert_eq!(line.arg_span(1), None);
+    }
+}
@@ -0,0 +1,193 @@
+use std::fmt::{Display, Formatter, Result};
+
+use super::{Config, Expr, Line, Statement, parser::is_word};
+
+/// Writes `s` as a quoted string.
+fn quoted(f: &mut Formatter<'_>, s: &str) -> Result {
+    f.write_str("\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\t' => f.write_str("\\t")?,
+            '\r' => f.write_str("\\r")?,
+            '\x07' => f.write_str("\\a")?,
+            '\x08' => f.write_str("\\b")?,
+            '\x0c' => f.write_str("\\f")?,
+            '\x0b' => f.write_str("\\v")?,
+            c => write!(f, "{c}")?,
+        }
+    }
+    f.write_str("\"")
+}
+
+/// Writes `s` bare if possible, otherwise quoted.
+fn name(f: &mut Formatter<'_>, s: &str) -> Result {
+    if is_word(s) {
+        f.write_str(s)
+    } else {
+        quoted(f, s)
+    }
+}
+
+impl Display for Config {
+    /// Writes the configuration in the syntax libsensors reads, keeping comments and indentation.
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        for line in &self.lines {
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Line {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str(&self.indent)?;
+        if let Some(source) = &self.source
+            && source.statement == self.statement && source.comment == self.comment {
+            return f.write_str(&source.text)
+        }
+        if let Some(statement) = &self.statement {
+            write!(f, "{statement}")?;
+        }
+        if let Some(comment) = &self.comment {
+            if self.statement.is_some() {
+                f.write_str(" ")?;
+            }
+            write!(f, "#{comment}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Statement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str(self.keyword())?;
+        match self {
+            Self::Bus { bus, adapter } => {
+                f.write_str(" ")?;
+                quoted(f, bus)?;
+                f.write_str(" ")?;
+                quoted(f, adapter)
+            },
+            Self::Chip { names } => {
+                for chip in names {
+                    f.write_str(" ")?;
+                    quoted(f, chip)?;
+                }
+                Ok(())
+            },
+            Self::Label { feature, label } => {
+                f.write_str(" ")?;
+                name(f, feature)?;
+                f.write_str(" ")?;
+                quoted(f, label)
+            },
+            Self::Compute { feature, from_raw, to_raw } => {
+                f.write_str(" ")?;
+                name(f, feature)?;
+                write!(f, " {from_raw}, {to_raw}")
+            },
+            Self::Set { subfeature, value } => {
+                f.write_str(" ")?;
+                name(f, subfeature)?;
+                write!(f, " {value}")
+            },
+            Self::Ignore { feature } => {
+                f.write_str(" ")?;
+                name(f, feature)
+            },
+        }
+    }
+}
+
+impl Expr {
+    /// Binding strength of the outermost operation; higher binds tighter.
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::Binary(_, op, _) => op.precedence(),
+            _ => 3,
+        }
+    }
+
+    fn write_operand(&self, f: &mut Formatter<'_>, parenthesise: bool) -> Result {
+        if parenthesise {
+            write!(f, "({self})")
+        } else {
+            write!(f, "{self}")
+        }
+    }
+}
+impl Display for Expr {
+    /// Writes the expression with as few parentheses as needed to parse back into the same tree.
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Number(n) => write!(f, "{n}"),
+            Self::Raw => f.write_str("@"),
+            Self::Feature(feature) => name(f, feature),
+            Self::Neg(x) | Self::Exp(x) | Self::Ln(x) => {
+                f.write_str(match self {
+                    Self::Neg(_) => "-",
+                    Self::Exp(_) => "^",
+                    _ => "`",
+                })?;
+                x.write_operand(f, x.precedence() < 3)
+            },
+            Self::Binary(left, op, right) => {
+                left.write_operand(f, left.precedence() < op.precedence())?;
+                write!(f, "{}", op.as_char())?;
+                // Operators are left-associative, so an equally strong right side needs parentheses.
+                right.write_operand(f, right.precedence() <= op.precedence())
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::BinOp;
+
+    use super::*;
+
+    fn num(n: f64) -> Expr {
+        Expr::Number(n)
+    }
+
+    #[test]
+    fn expressions_get_minimal_parentheses() {
+        let cases = [
+            (Expr::binary(num(2.0), BinOp::Sub, Expr::binary(num(3.0), BinOp::Sub, num(4.0))), "2-(3-4)"),
+            (Expr::binary(Expr::binary(num(2.0), BinOp::Sub, num(3.0)), BinOp::Sub, num(4.0)), "2-3-4"),
+            (Expr::binary(Expr::binary(num(1.0), BinOp::Add, num(2.0)), BinOp::Mul, Expr::Raw), "(1+2)*@"),
+            (Expr::binary(num(1.0), BinOp::Add, Expr::binary(num(2.0), BinOp::Mul, Expr::Raw)), "1+2*@"),
+            (Expr::Neg(Box::new(Expr::binary(Expr::Raw, BinOp::Add, num(1.0)))), "-(@+1)"),
+            (Expr::binary(Expr::Neg(Box::new(Expr::Raw)), BinOp::Div, num(0.5)), "-@/0.5"),
+            (Expr::Exp(Box::new(Expr::Feature("in0 input".to_owned()))), "^\"in0 input\""),
+        ];
+        for (expr, text) in cases {
+            assert_eq!(expr.to_string(), text);
+            assert_eq!(text.parse::<Expr>().unwrap(), expr, "{text}");
+        }
+    }
+
+    #[test]
+    fn strings_are_escaped() {
+        let statement = Statement::Label { feature: "temp1".to_owned(), label: "a \"b\" \\ c\n".to_owned() };
+        assert_eq!(statement.to_string(), r#"label temp1 "a \"b\" \\ c\n""#);
+        let config = Config::parse(&format!("chip \"lm78-*\"\n{statement}\n")).unwrap();
+        assert_eq!(config.statements().nth(1), Some(&statement));
+    }
+
+    #[test]
+    fn modified_lines_are_written_canonically() {
+        let src = "chip \"lm78-*\"\n    set in0_min   1.2 * 0.95   # keep\n\tignore fan3\n";
+        let mut config = Config::parse(src).unwrap();
+        config.lines[1].statement = Some(Statement::Set { subfeature: "in0_min".to_owned(), value: num(1.14) });
+        config.push(Statement::Ignore { feature: "fan 4".to_owned() });
+        assert_eq!(
+            config.to_string(),
+            "chip \"lm78-*\"\n    set in0_min 1.14 # keep\n\tignore fan3\n    ignore \"fan 4\"\n",
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use std::{fs, io, path::{Path, PathBuf}};
+
+/// Metadata about the device behind a chip, read from its sysfs directory.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DeviceInfo {
+    /// The sysfs directory of the chip (e.g. `/sys/class/hwmon/hwmon3`).
+    pub sysfs_path: PathBuf,
+    /// The N in `hwmonN`, if the directory is named like that.
+    pub hwmon_index: Option<u32>,
+    /// The name of the kernel driver bound to the device (from the `device/driver` symlink).
+    pub driver: Option<String>,
+    /// The resolved path of the underlying device (e.g. `/sys/devices/pci0000:00/0000:00:18.3`).
+    pub device_path: Option<PathBuf>,
+}
+impl DeviceInfo {
+    /// Reads the device metadata of the chip whose sysfs directory is `path`.
+    ///
+    /// Missing `device` or `driver` links (e.g. for virtual devices) are reported as None,
+    /// any other I/O error is returned.
+    /// This only looks at the given directory, so it also works with a fake sysfs tree.
+    pub fn from_sysfs_path(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        if !fs::metadata(path)?.is_dir() {
+            return Err(io::Error::new(io::ErrorKind::NotADirectory, "sysfs path is not a directory"))
+        }
+        let hwmon_index = path.file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("hwmon"))
+            .and_then(|index| index.parse().ok());
+        let driver = not_found_as_none(fs::read_link(path.join("device/driver")))?
+            .and_then(|driver| driver.file_name().map(|name| name.to_string_lossy().into_owned()));
+        let device_path = not_found_as_none(fs::canonicalize(path.join("device")))?;
+        Ok(Self { sysfs_path: path.to_owned(), hwmon_index, driver, device_path })
+    }
+}
+
+fn not_found_as_none<T>(res: io::Result<T>) -> io::Result<Option<T>> {
+    match res {
+        Ok(x) => Ok(Some(x)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::symlink;
+
+    use super::*;
+
+    /// Builds `sys/devices/platform/nct6775.656/hwmon/hwmonN` bound to `drivers/nct6775`
+    /// and links it from `sys/class/hwmon/hwmonN`, like the kernel does.
+    fn fake_sysfs(root: &Path, index: u32, bound: bool) -> (PathBuf, PathBuf) {
+        let device = root.join("sys/devices/platform/nct6775.656");
+        let hwmon = device.join(format!("hwmon/hwmon{index}"));
+        fs::create_dir_all(&hwmon).unwrap();
+        symlink("../..", hwmon.join("device")).unwrap();
+        if bound {
+            let driver = root.join("sys/bus/platform/drivers/nct6775");
+            fs::create_dir_all(&driver).unwrap();
+            symlink(&driver, device.join("driver")).unwrap();
+        }
+        let class = root.join("sys/class/hwmon");
+        fs::create_dir_all(&class).unwrap();
+        let link = class.join(format!("hwmon{index}"));
+        symlink(&hwmon, &link).unwrap();
+        (link, device)
+    }
+
+    #[test]
+    fn reads_index_driver_and_device() {
+        let root = tempfile::tempdir().unwrap();
+        let (path, device) = fake_sysfs(root.path(), 3, true);
+        let info = DeviceInfo::from_sysfs_path(&path).unwrap();
+        assert_eq!(info.sysfs_path, path);
+        assert_eq!(info.hwmon_index, Some(3));
+        assert_eq!(info.driver.as_deref(), Some("nct6775"));
+        assert_eq!(info.device_path, Some(fs::canonicalize(device).unwrap()));
+    }
+
+    #[test]
+    fn missing_driver_link_is_none() {
+        let root = tempfile::tempdir().unwrap();
+        let (path, device) = fake_sysfs(root.path(), 0, false);
+        let info = DeviceInfo::from_sysfs_path(&path).unwrap();
+        assert_eq!(info.hwmon_index, Some(0));
+        assert_eq!(info.driver, None);
+        assert_eq!(info.device_path, Some(fs::canonicalize(device).unwrap()));
+    }
+
+    #[test]
+    fn missing_device_link_is_none() {
+        let root = tempfile::tempdir().unwrap();
+        let path = root.path().join("thermal");
+        fs::create_dir(&path).unwrap();
+        let info = DeviceInfo::from_sysfs_path(&path).unwrap();
+        assert_eq!(info.hwmon_index, None);
+        assert_eq!(info.driver, None);
+        assert_eq!(info.device_path, None);
+    }
+
+    #[test]
+    fn file_is_not_a_directory() {
+        let root = tempfile::tempdir().unwrap();
+        let path = root.path().join("hwmon0");
+        fs::write(&path, "").unwrap();
+        let e = DeviceInfo::from_sysfs_path(&path).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::NotADirectory);
+    }
+}
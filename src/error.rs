@@ -1,10 +1,68 @@
 use std::{error::Error as StdError, ffi::{c_int, c_uint}, fmt::Display, str::Utf8Error};
 
+use crate::{ChipName, LibSensors, Version, feature::FeatureType, ffi, subfeature::SubfeatureType, utils::try_cstr};
+
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// The kind of error reported by libsensors (the `SENSORS_ERR_*` constants).
+#[repr(u32)]
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::FromRepr)]
+pub enum ErrorKind {
+    /// Wildcard found in chip name
+    Wildcards = ffi::SENSORS_ERR_WILDCARDS,
+    /// No such subfeature known
+    NoEntry = ffi::SENSORS_ERR_NO_ENTRY,
+    /// Can't read
+    AccessRead = ffi::SENSORS_ERR_ACCESS_R,
+    /// Kernel interface error
+    Kernel = ffi::SENSORS_ERR_KERNEL,
+    /// Divide by zero
+    DivZero = ffi::SENSORS_ERR_DIV_ZERO,
+    /// Can't parse chip name
+    ChipName = ffi::SENSORS_ERR_CHIP_NAME,
+    /// Can't parse bus name
+    BusName = ffi::SENSORS_ERR_BUS_NAME,
+    /// General parse error
+    Parse = ffi::SENSORS_ERR_PARSE,
+    /// Can't write
+    AccessWrite = ffi::SENSORS_ERR_ACCESS_W,
+    /// I/O error
+    Io = ffi::SENSORS_ERR_IO,
+    /// Evaluation recurses too deep
+    Recursion = ffi::SENSORS_ERR_RECURSION,
+}
+impl ErrorKind {
+    /// The (negative) code libsensors returns for this kind of error.
+    pub fn code(self) -> c_int {
+        -(self as c_int)
+    }
+}
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // These are the same messages sensors_strerror returns.
+        f.write_str(match self {
+            Self::Wildcards   => "Wildcard found in chip name",
+            Self::NoEntry     => "No such subfeature known",
+            Self::AccessRead  => "Can't read",
+            Self::Kernel      => "Kernel interface error",
+            Self::DivZero     => "Divide by zero",
+            Self::ChipName    => "Can't parse chip name",
+            Self::BusName     => "Can't parse bus name",
+            Self::Parse       => "General parse error",
+            Self::AccessWrite => "Can't write",
+            Self::Io          => "I/O error",
+            Self::Recursion   => "Evaluation recurses too deep",
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SensorsError {
-    pub code: i32
+    pub code: i32,
+    /// The message from sensors_strerror, if it could be retrieved.
+    message: Option<String>,
+    context: Option<ErrorContext>,
 }
 impl SensorsError {
     /// Converts an i32 into a result with this error type.
@@ -12,42 +70,213 @@ impl SensorsError {
     /// else Err with the correct Error structure
     pub(crate) fn convert_cint(code: c_int) -> std::result::Result<c_uint, Self> {
         if code < 0 {
-            Err(SensorsError { code })
+            Err(SensorsError { code, message: None, context: None })
         } else {
             Ok(code as c_uint)
         }
     }
+
+    /// Like [`Self::convert_cint`], but also retrieves the error message from libsensors.
+    pub(crate) fn convert_cint_with(lib: &LibSensors, code: c_int) -> std::result::Result<c_uint, Self> {
+        Self::convert_cint(code)
+            .map_err(|mut e| {
+                let strerror = lib._sensors_strerror();
+                // SAFETY: sensors_strerror accepts any error code and returns a static C-string.
+                //  It is copied before the library can go away.
+                e.message = unsafe { try_cstr(strerror(code)) }.map(|m| m.to_string_lossy().into_owned());
+                e
+            })
+    }
+
+    /// The sensor this error occurred on, if known.
+    pub fn context(&self) -> Option<&ErrorContext> {
+        self.context.as_ref()
+    }
+
+    /// The kind of this error, or None if libsensors does not define its code.
+    pub fn kind(&self) -> Option<ErrorKind> {
+        ErrorKind::from_repr(self.code.unsigned_abs())
+    }
+}
+impl From<ErrorKind> for SensorsError {
+    fn from(value: ErrorKind) -> Self {
+        Self { code: value.code(), message: None, context: None }
+    }
 }
 impl Display for SensorsError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Unknown error during libsensors call: {}", self.code)
+        if let Some(context) = &self.context {
+            write!(f, "{context}: ")?;
+        }
+        match &self.message {
+            Some(message) => write!(f, "{message} ({})", self.code),
+            None => match self.kind() {
+                Some(kind) => write!(f, "{kind} ({})", self.code),
+                None => write!(f, "Unknown error ({})", self.code),
+            },
+        }
     }
 }
 impl StdError for SensorsError { }
 
+/// The operation that was being performed when an error occurred.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    /// Reading the value of a subfeature.
+    Get,
+    /// Writing the value of a subfeature.
+    Set,
+    /// Getting the label of a feature.
+    Label,
+}
+impl Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Get => "get",
+            Self::Set => "set",
+            Self::Label => "label",
+        })
+    }
+}
+
+/// Describes which sensor was involved in an error.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    pub chip: Option<ChipName>,
+    pub feature: Option<String>,
+    pub subfeature: Option<String>,
+    pub operation: Option<Operation>,
+}
+impl ErrorContext {
+    pub fn with_operation(mut self, operation: Operation) -> Self {
+        self.operation = Some(operation);
+        self
+    }
+}
+impl Display for ErrorContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(operation) = self.operation {
+            write!(f, "{operation} ")?;
+        }
+        match &self.chip {
+            Some(chip) => write!(f, "{chip}")?,
+            None => write!(f, "<unknown chip>")?,
+        }
+        for part in [&self.feature, &self.subfeature].into_iter().flatten() {
+            write!(f, "/{part}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Any error of this crate, except those from loading libsensors (see [`LoadingError`](crate::LoadingError)).
+/// 
+/// Which variants exist depends on the enabled features (e.g. [`Error::Loading`] only with `dlopen`),
+/// so matches must have a wildcard arm.
+#[non_exhaustive]
 #[derive(Debug)]
 pub enum Error {
+    /// An error returned by libsensors, with the sensor it occurred on if known (see [`Error::context`]).
     Sensors(SensorsError),
+    /// A symbol could not be resolved in the loaded library.
+    #[cfg(feature = "dlopen")]
     Loading(libloading::Error),
     Utf8(Utf8Error),
     UnexpectedWildcard(i64),
+    /// A string could not be parsed on the Rust side (e.g. a chip name or a version).
+    /// 
+    /// Errors from libsensors' own parsers are reported as [`Error::Sensors`] instead.
+    Parse {
+        /// What the string should have been.
+        what: &'static str,
+        input: String,
+    },
+    /// libsensors returned a pointer that is null or misaligned where this was not expected.
+    InvalidPointer {
+        /// What the pointer should have pointed to.
+        what: &'static str,
+        /// The chip or feature it was retrieved from, if any.
+        context: Option<ErrorContext>,
+    },
+    /// A subfeature type was used with a feature of a different type.
+    SubfeatureTypeMismatch {
+        feature: FeatureType,
+        subfeature: SubfeatureType,
+    },
+    /// The loaded libsensors is too old for what was requested.
+    Unsupported {
+        what: &'static str,
+        /// The first version that supports it.
+        required: Version,
+    },
+}
+impl Error {
+    pub(crate) fn parse(what: &'static str, input: &str) -> Self {
+        Self::Parse { what, input: input.to_owned() }
+    }
+
+    /// Attaches information about the sensor this error occurred on, unless it already has some.
+    /// 
+    /// Only [`Error::Sensors`] and [`Error::InvalidPointer`] carry context; other errors are returned unchanged.
+    pub fn with_context(mut self, context: ErrorContext) -> Self {
+        let slot = match &mut self {
+            Self::Sensors(e) => &mut e.context,
+            Self::InvalidPointer { context: slot, .. } => slot,
+            _ => return self,
+        };
+        slot.get_or_insert(context);
+        self
+    }
+
+    /// The sensor this error occurred on, if known.
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Self::Sensors(e) => e.context(),
+            Self::InvalidPointer { context, .. } => context.as_ref(),
+            _ => None,
+        }
+    }
 }
 impl Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Sensors(e) => write!(f, "Sensors({e})"),
-            Self::Loading(e) => write!(f, "Loading({e})"),
-            Self::Utf8(e) => write!(f, "Utf8({e})"),
+            // The wrapped errors are the source.
+            Self::Sensors(_) => write!(f, "libsensors error"),
+            #[cfg(feature = "dlopen")]
+            Self::Loading(_) => write!(f, "Failed to resolve a libsensors symbol"),
+            Self::Utf8(_) => write!(f, "Invalid UTF-8 from libsensors"),
             Self::UnexpectedWildcard(value) => write!(f, "Unexpected wildcard value: {value}"),
+            Self::Parse { what, input } => write!(f, "Invalid {what}: {input:?}"),
+            Self::InvalidPointer { what, context: Some(context) } => write!(f, "{context}: Invalid pointer to {what}"),
+            Self::InvalidPointer { what, context: None } => write!(f, "Invalid pointer to {what}"),
+            Self::SubfeatureTypeMismatch { feature, subfeature } => write!(f, "Subfeature type {subfeature:?} does not belong to feature type {feature:?}"),
+            Self::Unsupported { what, required } => write!(f, "{what} requires libsensors {required} or newer"),
         }
     }
 }
-impl StdError for Error { }
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Sensors(e) => Some(e),
+            #[cfg(feature = "dlopen")]
+            Self::Loading(e) => Some(e),
+            Self::Utf8(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+impl From<ErrorKind> for Error {
+    fn from(value: ErrorKind) -> Self {
+        Self::Sensors(value.into())
+    }
+}
 impl From<SensorsError> for Error {
     fn from(value: SensorsError) -> Self {
         Self::Sensors(value)
     }
 }
+#[cfg(feature = "dlopen")]
 impl From<libloading::Error> for Error {
     fn from(value: libloading::Error) -> Self {
         Self::Loading(value)
@@ -57,4 +286,42 @@ impl From<Utf8Error> for Error {
     fn from(value: Utf8Error) -> Self {
         Self::Utf8(value)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(feature: &str) -> ErrorContext {
+        ErrorContext {
+            chip: Some("coretemp-isa-0000".parse().unwrap()),
+            feature: Some(feature.to_owned()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn context_keeps_variants_matchable() {
+        let e = Error::from(SensorsError::from(ErrorKind::AccessRead))
+            .with_context(context("temp1").with_operation(Operation::Get));
+        let Error::Sensors(inner) = &e else { panic!("{e:?}") };
+        assert_eq!(inner.kind(), Some(ErrorKind::AccessRead));
+        assert_eq!(e.context(), Some(&context("temp1").with_operation(Operation::Get)));
+        assert_eq!(e.to_string(), "libsensors error");
+        assert_eq!(e.source().unwrap().to_string(), "get coretemp-isa-0000/temp1: Can't read (-3)");
+    }
+
+    #[test]
+    fn innermost_context_wins() {
+        let e = Error::InvalidPointer { what: "subfeature", context: None }
+            .with_context(context("temp1"))
+            .with_context(context("temp2"));
+        assert_eq!(e.context(), Some(&context("temp1")));
+    }
+
+    #[test]
+    fn context_free_variants_are_unchanged() {
+        let e = Error::UnexpectedWildcard(-1).with_context(context("temp1"));
+        assert!(matches!(e, Error::UnexpectedWildcard(-1)));
+        assert_eq!(e.context(), None);
+    }
+}
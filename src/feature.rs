@@ -1,8 +1,6 @@
 use std::{error::Error as StdError, ffi::{CStr, CString, c_char, c_int}, fmt::Display, result::Result as StdResult};
 
-use libloading::Symbol;
-
-use crate::{GetAllSubfeatures, LibSensors, error::{Error, Result}, ffi::{self, sensors_chip_name, sensors_feature, sensors_subfeature, sensors_subfeature_type}, subfeature::Subfeature, utils::{GLibCBox, ptr_to_ref}};
+use crate::{ChipName, LibSensors, error::{Error, ErrorContext, Result}, ffi::{self, sensors_chip_name, sensors_feature, sensors_subfeature, sensors_subfeature_type}, subfeature::{GenericSubfeature, Subfeature, SubfeatureType}, utils::{GLibCBox, checked_ptr_to_ref, try_cstr}};
 
 #[derive(Debug)]
 pub enum GetLabelError {
@@ -11,18 +9,21 @@ pub enum GetLabelError {
 }
 impl Display for GetLabelError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let (pre, e): (&'static str, Option<&dyn StdError>) = match self {
-            Self::GetLabelFailed => ("GetLabelFailed", None),
-            Self::LibSensors(e) => ("LibSensors", Some(e))
+        let pre = match self {
+            Self::GetLabelFailed => "GetLabelFailed",
+            Self::LibSensors(_) => "LibSensors"
         };
-        if let Some(e) = e {
-            write!(f, "GetLabelError({}: {})", pre, e)
-        } else {
-            write!(f, "GetLabelError({})", pre)
+        write!(f, "GetLabelError({})", pre)
+    }
+}
+impl StdError for GetLabelError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::GetLabelFailed => None,
+            Self::LibSensors(e) => Some(e)
         }
     }
 }
-impl StdError for GetLabelError { }
 
 #[derive(Debug)]
 pub struct Feature<'lib> {
@@ -39,32 +40,54 @@ impl<'lib> Feature<'lib> {
         })
     }
 
+    /// Describes this feature for errors.
+    pub(crate) fn error_context(&self) -> ErrorContext {
+        ErrorContext {
+            // SAFETY: Features are only created from a Chip, so the prefix of self.chip has been checked in Chip::new.
+            chip: Some(unsafe { ChipName::from_raw(self.chip) }),
+            feature: self.get_name().map(|name| name.to_string_lossy().into_owned()),
+            ..Default::default()
+        }
+    }
+
+    /// The number of this feature, which is unique and stable within its chip.
+    pub fn number(&self) -> c_int {
+        self.raw.number
+    }
+
     pub fn get_type(&self) -> FeatureType {
         self.type_
     }
 
-    pub fn get_subfeature_by_type(&self, type_: sensors_subfeature_type::Type) -> Result<Option<Subfeature<'lib>>> {
-        self.lib._sensors_get_subfeature()
-            .map(|sym| {
-                unsafe { ptr_to_ref(sym(self.chip, self.raw, type_)) }
-                    .expect("get_subfeature_by_type: ptr is misaligned")
-            })
-            .map(|raw_opt| raw_opt.map(|raw| Subfeature::new(raw, self.chip, self.lib)))
-            .map_err(Into::into)
+    /// Gets the subfeature of the given type.
+    /// 
+    /// Returns [`Error::SubfeatureTypeMismatch`] if `type_` does not belong to the type of this feature.
+    pub fn get_subfeature_by_type(&self, type_: SubfeatureType) -> Result<Option<Subfeature<'lib>>> {
+        if type_.feature_type() != Some(self.type_) {
+            return Err(Error::SubfeatureTypeMismatch { feature: self.type_, subfeature: type_ })
+        }
+        let fun = self.lib._sensors_get_subfeature();
+        unsafe { checked_ptr_to_ref(fun(self.chip, self.raw, type_ as sensors_subfeature_type::Type), "subfeature") }
+            .map(|raw_opt| raw_opt.map(|raw| Subfeature::new(raw, self.raw, self.chip, self.lib)))
+            .map_err(|e| e.with_context(self.error_context()))
+    }
+
+    /// Gets the subfeature with the given role, if this feature has one.
+    pub fn get_generic(&self, generic: GenericSubfeature) -> Result<Option<Subfeature<'lib>>> {
+        match generic.to_subfeature_type(self.type_) {
+            Some(type_) => self.get_subfeature_by_type(type_),
+            None => Ok(None),
+        }
     }
 
     pub fn get_subfeature(&self, mut index: c_int) -> Result<Option<&'lib sensors_subfeature>> {
-        self.lib._sensors_get_all_subfeatures()
-            .map(|sym|
-                unsafe { ptr_to_ref(sym(self.chip, self.raw, &mut index)) }.unwrap()
-            )
-            .map_err(Into::into)
+        let fun = self.lib._sensors_get_all_subfeatures();
+        unsafe { checked_ptr_to_ref(fun(self.chip, self.raw, &mut index), "subfeature") }
+            .map_err(|e| e.with_context(self.error_context()))
     }
 
-    pub fn get_subfeatures(&self) -> Result<SubfeatureIterator<'lib>> {
-        self.lib._sensors_get_all_subfeatures()
-            .map(|sym| SubfeatureIterator::new(sym, self.chip, self.raw, self.lib))
-            .map_err(Into::into)
+    pub fn get_subfeatures(&self) -> SubfeatureIterator<'lib> {
+        SubfeatureIterator::new(self.chip, self.raw, self.lib)
     }
 
 
@@ -72,16 +95,16 @@ impl<'lib> Feature<'lib> {
     /// 
     /// The resulting GLibCBox contains a maybe-null pointer that is guaranteed to point to a valid c-string.
     /// GLibCBox also guarantees that the allocated memory will be correctly disposed when the box goes out of scope.
-    fn get_label_extremely_raw(&self) -> Result<GLibCBox<c_char>> {
+    fn get_label_extremely_raw(&self) -> GLibCBox<c_char> {
         // Note that this function is not unsafe, even though we return unsafe stuff.
         // This is because every safety guarantee required by the functions we use is accounted for.
-        let get_label = self.lib._sensors_get_label()?;
-        let free = self.lib._free()?;
+        let get_label = self.lib._sensors_get_label();
+        let free = self.lib._free();
         // SAFETY:
         //  We must pass in valid chip and feature pointers (we do, because references).
         //  The resulting ptr was allocated by libsensors' free function (which is what we are passing into GLibCBox here)
         //  Using GLibCBox also guarantees that the pointer is freed when it goes out of scope (GLibC's free function cannot error)
-        Ok(unsafe { GLibCBox::from_raw(get_label(self.chip, self.raw), *free) })
+        unsafe { GLibCBox::from_raw(get_label(self.chip, self.raw), free) }
     }
 
     /// Get the label for this feature.
@@ -89,20 +112,18 @@ impl<'lib> Feature<'lib> {
     /// Note that this function returns a [`CString`].
     /// If you want a [`String`], use [`Self::get_label`] instead.
     pub fn get_label_raw(&self) -> Result<Option<CString>> {
-        self.get_label_extremely_raw()
-            .map(|raw| {
-                if raw.is_null() {
-                    None
-                } else {
-                    // SAFETY:
-                    //  - Libsensors guarantees us that the returned pointer is either null or points to a valid c-string.
-                    //    - contains null terminator, valid for reads up to the null terminator
-                    //  - we own that memory now and Libsensors won't modify it either.
-                    //     (Nor do we, as it will be freed after to_owned)
-                    //  - TODO: Technically we can't know whether strlen(raw) <= isize::MAX
-                    Some(unsafe { CStr::from_ptr(*raw) }.to_owned())
-                }
-            })
+        let raw = self.get_label_extremely_raw();
+        if raw.is_null() {
+            Ok(None)
+        } else {
+            // SAFETY:
+            //  - Libsensors guarantees us that the returned pointer is either null or points to a valid c-string.
+            //    - contains null terminator, valid for reads up to the null terminator
+            //  - we own that memory now and Libsensors won't modify it either.
+            //     (Nor do we, as it will be freed after to_owned)
+            //  - TODO: Technically we can't know whether strlen(raw) <= isize::MAX
+            Ok(Some(unsafe { CStr::from_ptr(*raw) }.to_owned()))
+        }
     }
 
     pub fn get_label(&self) -> StdResult<String, GetLabelError> {
@@ -110,37 +131,50 @@ impl<'lib> Feature<'lib> {
             .map_err(GetLabelError::LibSensors)?
             .ok_or(GetLabelError::GetLabelFailed)?
             .into_string()
-            .map_err(|e| GetLabelError::LibSensors(e.utf8_error().into()))
+            .map_err(|e| GetLabelError::LibSensors(Error::from(e.utf8_error())))
     }
 
-    pub fn get_name(&self) -> &CStr {
-        unsafe { CStr::from_ptr(self.raw.name) }
+    pub fn get_name(&self) -> Option<&CStr> {
+        let raw = self.raw.name;
+        if raw.is_null() {
+            None
+        } else {
+            Some(unsafe { CStr::from_ptr(raw) })
+        }
     }
 }
 
 pub struct SubfeatureIterator<'lib> {
     lib: &'lib LibSensors,
-    fun: Symbol<'lib, GetAllSubfeatures>,
     chip: &'lib sensors_chip_name,
     feature: &'lib sensors_feature,
     index: c_int
 }
 impl<'lib> SubfeatureIterator<'lib> {
-    pub fn new(fun: Symbol<'lib, GetAllSubfeatures>, chip: &'lib sensors_chip_name, feature: &'lib sensors_feature, lib: &'lib LibSensors) -> Self {
-        Self { lib, fun, chip, feature, index: 0 }
+    pub fn new(chip: &'lib sensors_chip_name, feature: &'lib sensors_feature, lib: &'lib LibSensors) -> Self {
+        Self { lib, chip, feature, index: 0 }
     }
 }
 impl<'lib> Iterator for SubfeatureIterator<'lib> {
-    type Item = Subfeature<'lib>;
+    type Item = Result<Subfeature<'lib>>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        unsafe { ptr_to_ref((self.fun)(self.chip, self.feature, &mut self.index)) }.unwrap()
-            .map(|raw| Subfeature::new(raw, self.chip, self.lib))
+        unsafe { checked_ptr_to_ref((self.lib._sensors_get_all_subfeatures())(self.chip, self.feature, &mut self.index), "subfeature") }
+            .transpose()
+            .map(|raw| raw
+                .map(|raw| Subfeature::new(raw, self.feature, self.chip, self.lib))
+                .map_err(|e| e.with_context(ErrorContext {
+                    // SAFETY: The iterator comes from a Feature of a Chip, whose prefix has been checked in Chip::new.
+                    chip: Some(unsafe { ChipName::from_raw(self.chip) }),
+                    // SAFETY: Feature names are C-strings owned by libsensors.
+                    feature: unsafe { try_cstr(self.feature.name) }.map(|name| name.to_string_lossy().into_owned()),
+                    ..Default::default()
+                })))
     }
 }
 
 #[repr(u32)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, strum::FromRepr)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, strum::FromRepr)]
 pub enum FeatureType {
     In = 0,
     Fan = 1,
@@ -4,7 +4,113 @@
 #![allow(unused)]
 #![allow(clippy::all)]
 
+#[cfg(feature = "bindgen")]
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+#[cfg(all(feature = "bundled-bindings", not(feature = "bindgen")))]
+include!("bundled_bindings.rs");
+#[cfg(not(any(feature = "bindgen", feature = "bundled-bindings")))]
+compile_error!("either the `bundled-bindings` or the `bindgen` feature must be enabled");
+
+/// The bundled bindings, only used to check them against the generated ones.
+#[cfg(all(feature = "bundled-bindings", feature = "bindgen"))]
+mod bundled {
+    include!("bundled_bindings.rs");
+}
+
+/// Asserts at compile time that a struct has the same layout in the generated and the bundled bindings.
+#[cfg(all(feature = "bundled-bindings", feature = "bindgen"))]
+macro_rules! assert_same_layout {
+    ($name:ident { $($field:ident),* }) => {
+        const _: () = {
+            assert!(size_of::<$name>() == size_of::<bundled::$name>(), concat!("size of ", stringify!($name), " differs"));
+            assert!(align_of::<$name>() == align_of::<bundled::$name>(), concat!("alignment of ", stringify!($name), " differs"));
+            $(assert!(
+                std::mem::offset_of!($name, $field) == std::mem::offset_of!(bundled::$name, $field),
+                concat!("offset of ", stringify!($name), "::", stringify!($field), " differs")
+            );)*
+        };
+    };
+}
+
+/// Asserts at compile time that constants have the same values in the generated and the bundled bindings.
+#[cfg(all(feature = "bundled-bindings", feature = "bindgen"))]
+macro_rules! assert_same_values {
+    (mod $module:ident: $($name:ident),* $(,)?) => {
+        $(const _: () = assert!(
+            $module::$name as i64 == bundled::$module::$name as i64,
+            concat!("value of ", stringify!($module), "::", stringify!($name), " differs")
+        );)*
+    };
+    ($($name:ident),* $(,)?) => {
+        $(const _: () = assert!($name as i64 == bundled::$name as i64, concat!("value of ", stringify!($name), " differs"));)*
+    };
+}
+
+/// Compares the bundled bindings with the ones generated from the installed headers.
+///
+/// Default builds do not generate bindings, so run `cargo check --features bindgen` (which keeps the default
+/// `bundled-bindings`) against the libsensors 3.6 headers after changing `bundled_bindings.rs`.
+#[cfg(all(feature = "bundled-bindings", feature = "bindgen"))]
+mod layout_checks {
+    use super::*;
+
+    assert_same_layout!(sensors_bus_id { type_, nr });
+    assert_same_layout!(sensors_chip_name { prefix, bus, addr, path });
+    assert_same_layout!(sensors_feature { name, number, type_, first_subfeature, padding1 });
+    assert_same_layout!(sensors_subfeature { name, number, type_, mapping, flags });
+
+    // The API version is left out: it changes with every minor release, without changing the ABI.
+    assert_same_values!(
+        SENSORS_CHIP_NAME_ADDR_ANY, SENSORS_BUS_TYPE_ANY, SENSORS_BUS_TYPE_I2C, SENSORS_BUS_TYPE_ISA,
+        SENSORS_BUS_TYPE_PCI, SENSORS_BUS_TYPE_SPI, SENSORS_BUS_TYPE_VIRTUAL, SENSORS_BUS_TYPE_ACPI,
+        SENSORS_BUS_TYPE_HID, SENSORS_BUS_TYPE_MDIO, SENSORS_BUS_TYPE_SCSI, SENSORS_BUS_NR_ANY,
+        SENSORS_BUS_NR_IGNORE, SENSORS_MODE_R, SENSORS_MODE_W, SENSORS_COMPUTE_MAPPING, SENSORS_ERR_WILDCARDS,
+        SENSORS_ERR_NO_ENTRY, SENSORS_ERR_ACCESS_R, SENSORS_ERR_KERNEL, SENSORS_ERR_DIV_ZERO,
+        SENSORS_ERR_CHIP_NAME, SENSORS_ERR_BUS_NAME, SENSORS_ERR_PARSE, SENSORS_ERR_ACCESS_W, SENSORS_ERR_IO,
+        SENSORS_ERR_RECURSION,
+    );
+    assert_same_values!(mod sensors_feature_type:
+        SENSORS_FEATURE_IN, SENSORS_FEATURE_FAN, SENSORS_FEATURE_TEMP, SENSORS_FEATURE_POWER,
+        SENSORS_FEATURE_ENERGY, SENSORS_FEATURE_CURR, SENSORS_FEATURE_HUMIDITY, SENSORS_FEATURE_MAX_MAIN,
+        SENSORS_FEATURE_VID, SENSORS_FEATURE_INTRUSION, SENSORS_FEATURE_MAX_OTHER,
+        SENSORS_FEATURE_BEEP_ENABLE, SENSORS_FEATURE_MAX, SENSORS_FEATURE_UNKNOWN,
+    );
+    assert_same_values!(mod sensors_subfeature_type:
+        SENSORS_SUBFEATURE_IN_INPUT, SENSORS_SUBFEATURE_IN_MIN, SENSORS_SUBFEATURE_IN_MAX,
+        SENSORS_SUBFEATURE_IN_LCRIT, SENSORS_SUBFEATURE_IN_CRIT, SENSORS_SUBFEATURE_IN_AVERAGE,
+        SENSORS_SUBFEATURE_IN_LOWEST, SENSORS_SUBFEATURE_IN_HIGHEST, SENSORS_SUBFEATURE_IN_ALARM,
+        SENSORS_SUBFEATURE_IN_MIN_ALARM, SENSORS_SUBFEATURE_IN_MAX_ALARM, SENSORS_SUBFEATURE_IN_BEEP,
+        SENSORS_SUBFEATURE_IN_LCRIT_ALARM, SENSORS_SUBFEATURE_IN_CRIT_ALARM, SENSORS_SUBFEATURE_FAN_INPUT,
+        SENSORS_SUBFEATURE_FAN_MIN, SENSORS_SUBFEATURE_FAN_MAX, SENSORS_SUBFEATURE_FAN_ALARM,
+        SENSORS_SUBFEATURE_FAN_FAULT, SENSORS_SUBFEATURE_FAN_DIV, SENSORS_SUBFEATURE_FAN_BEEP,
+        SENSORS_SUBFEATURE_FAN_PULSES, SENSORS_SUBFEATURE_FAN_MIN_ALARM, SENSORS_SUBFEATURE_FAN_MAX_ALARM,
+        SENSORS_SUBFEATURE_TEMP_INPUT, SENSORS_SUBFEATURE_TEMP_MAX, SENSORS_SUBFEATURE_TEMP_MAX_HYST,
+        SENSORS_SUBFEATURE_TEMP_MIN, SENSORS_SUBFEATURE_TEMP_CRIT, SENSORS_SUBFEATURE_TEMP_CRIT_HYST,
+        SENSORS_SUBFEATURE_TEMP_LCRIT, SENSORS_SUBFEATURE_TEMP_EMERGENCY,
+        SENSORS_SUBFEATURE_TEMP_EMERGENCY_HYST, SENSORS_SUBFEATURE_TEMP_LOWEST,
+        SENSORS_SUBFEATURE_TEMP_HIGHEST, SENSORS_SUBFEATURE_TEMP_MIN_HYST, SENSORS_SUBFEATURE_TEMP_LCRIT_HYST,
+        SENSORS_SUBFEATURE_TEMP_ALARM, SENSORS_SUBFEATURE_TEMP_MAX_ALARM, SENSORS_SUBFEATURE_TEMP_MIN_ALARM,
+        SENSORS_SUBFEATURE_TEMP_CRIT_ALARM, SENSORS_SUBFEATURE_TEMP_FAULT, SENSORS_SUBFEATURE_TEMP_TYPE,
+        SENSORS_SUBFEATURE_TEMP_OFFSET, SENSORS_SUBFEATURE_TEMP_BEEP, SENSORS_SUBFEATURE_TEMP_EMERGENCY_ALARM,
+        SENSORS_SUBFEATURE_TEMP_LCRIT_ALARM, SENSORS_SUBFEATURE_POWER_AVERAGE,
+        SENSORS_SUBFEATURE_POWER_AVERAGE_HIGHEST, SENSORS_SUBFEATURE_POWER_AVERAGE_LOWEST,
+        SENSORS_SUBFEATURE_POWER_INPUT, SENSORS_SUBFEATURE_POWER_INPUT_HIGHEST,
+        SENSORS_SUBFEATURE_POWER_INPUT_LOWEST, SENSORS_SUBFEATURE_POWER_CAP,
+        SENSORS_SUBFEATURE_POWER_CAP_HYST, SENSORS_SUBFEATURE_POWER_MAX, SENSORS_SUBFEATURE_POWER_CRIT,
+        SENSORS_SUBFEATURE_POWER_MIN, SENSORS_SUBFEATURE_POWER_LCRIT,
+        SENSORS_SUBFEATURE_POWER_AVERAGE_INTERVAL, SENSORS_SUBFEATURE_POWER_ALARM,
+        SENSORS_SUBFEATURE_POWER_CAP_ALARM, SENSORS_SUBFEATURE_POWER_MAX_ALARM,
+        SENSORS_SUBFEATURE_POWER_CRIT_ALARM, SENSORS_SUBFEATURE_POWER_MIN_ALARM,
+        SENSORS_SUBFEATURE_POWER_LCRIT_ALARM, SENSORS_SUBFEATURE_ENERGY_INPUT, SENSORS_SUBFEATURE_CURR_INPUT,
+        SENSORS_SUBFEATURE_CURR_MIN, SENSORS_SUBFEATURE_CURR_MAX, SENSORS_SUBFEATURE_CURR_LCRIT,
+        SENSORS_SUBFEATURE_CURR_CRIT, SENSORS_SUBFEATURE_CURR_AVERAGE, SENSORS_SUBFEATURE_CURR_LOWEST,
+        SENSORS_SUBFEATURE_CURR_HIGHEST, SENSORS_SUBFEATURE_CURR_ALARM, SENSORS_SUBFEATURE_CURR_MIN_ALARM,
+        SENSORS_SUBFEATURE_CURR_MAX_ALARM, SENSORS_SUBFEATURE_CURR_BEEP, SENSORS_SUBFEATURE_CURR_LCRIT_ALARM,
+        SENSORS_SUBFEATURE_CURR_CRIT_ALARM, SENSORS_SUBFEATURE_HUMIDITY_INPUT, SENSORS_SUBFEATURE_VID,
+        SENSORS_SUBFEATURE_INTRUSION_ALARM, SENSORS_SUBFEATURE_INTRUSION_BEEP, SENSORS_SUBFEATURE_BEEP_ENABLE,
+        SENSORS_SUBFEATURE_UNKNOWN,
+    );
+}
 
 // use std::ffi::{c_char, c_int, c_short};
 
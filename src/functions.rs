@@ -0,0 +1,128 @@
+use std::ffi::{c_char, c_double, c_int, c_void};
+
+#[cfg(not(feature = "link"))]
+use libloading::Library;
+
+use crate::{ffi, utils::GLibCFree};
+
+pub(crate) type SensorsInit = unsafe extern "C" fn(*mut ffi::FILE) -> c_int;
+pub(crate) type SensorsCleanup = unsafe extern "C" fn();
+pub(crate) type GetAdapterName = unsafe extern "C" fn(*const ffi::sensors_bus_id) -> *const c_char;
+pub(crate) type GetLabel = unsafe extern "C" fn(*const ffi::sensors_chip_name, *const ffi::sensors_feature) -> *mut c_char;
+pub(crate) type GetValue = unsafe extern "C" fn(*const ffi::sensors_chip_name, c_int, *mut c_double) -> c_int;
+pub(crate) type SetValue = unsafe extern "C" fn(*const ffi::sensors_chip_name, c_int, c_double) -> c_int;
+pub(crate) type ParseChipName = unsafe extern "C" fn(*const c_char, *mut ffi::sensors_chip_name) -> c_int;
+pub(crate) type FreeChipName = unsafe extern "C" fn(*mut ffi::sensors_chip_name);
+pub(crate) type SnprintfChipName = unsafe extern "C" fn(*mut c_char, usize, *const ffi::sensors_chip_name) -> c_int;
+pub(crate) type GetDetectedChips = unsafe extern "C" fn(*const ffi::sensors_chip_name, *mut c_int) -> *const ffi::sensors_chip_name;
+pub(crate) type GetFeatures = unsafe extern "C" fn(*const ffi::sensors_chip_name, *mut c_int) -> *const ffi::sensors_feature;
+pub(crate) type GetAllSubfeatures = unsafe extern "C" fn(*const ffi::sensors_chip_name, *const ffi::sensors_feature, *mut c_int) -> *const ffi::sensors_subfeature;
+pub(crate) type GetSubfeature = unsafe extern "C" fn(*const ffi::sensors_chip_name, *const ffi::sensors_feature, ffi::sensors_subfeature_type::Type) -> *const ffi::sensors_subfeature;
+pub(crate) type DoChipSets = unsafe extern "C" fn(*const ffi::sensors_chip_name) -> c_int;
+pub(crate) type Strerror = unsafe extern "C" fn(c_int) -> *const c_char;
+pub(crate) type Fopen = unsafe extern "C" fn(*const c_char, *const c_char) -> *mut ffi::FILE;
+pub(crate) type Fmemopen = unsafe extern "C" fn(*mut c_void, usize, *const c_char) -> *mut ffi::FILE;
+pub(crate) type Fclose = unsafe extern "C" fn(*mut ffi::FILE) -> c_int;
+
+/// Every library function this crate calls.
+///
+/// With `dlopen`, these are resolved once when libsensors is loaded, so calling them later cannot fail.
+/// With `link`, this is [`Functions::LINKED`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Functions {
+    pub sensors_init: SensorsInit,
+    pub sensors_cleanup: SensorsCleanup,
+    pub sensors_get_adapter_name: GetAdapterName,
+    pub sensors_get_label: GetLabel,
+    pub sensors_get_value: GetValue,
+    pub sensors_set_value: SetValue,
+    pub sensors_parse_chip_name: ParseChipName,
+    pub sensors_free_chip_name: FreeChipName,
+    pub sensors_snprintf_chip_name: SnprintfChipName,
+    pub sensors_get_detected_chips: GetDetectedChips,
+    pub sensors_get_features: GetFeatures,
+    pub sensors_get_all_subfeatures: GetAllSubfeatures,
+    pub sensors_get_subfeature: GetSubfeature,
+    pub sensors_do_chip_sets: DoChipSets,
+    pub sensors_strerror: Strerror,
+    // libc functions, resolved through libsensors so that they match the libc it uses
+    pub free: GLibCFree,
+    pub fopen: Fopen,
+    pub fmemopen: Fmemopen,
+    pub fclose: Fclose,
+}
+#[cfg(not(feature = "link"))]
+impl Functions {
+    /// Resolves every function from `lib`.
+    ///
+    /// # Safety
+    /// `lib` must be libsensors, so that the symbols have the types declared here.
+    /// The resulting function pointers must not be called after `lib` has been dropped.
+    pub(crate) unsafe fn load(lib: &Library) -> std::result::Result<Self, libloading::Error> {
+        // SAFETY: The caller guarantees that the symbols have the declared types
+        //  and that the pointers do not outlive the library.
+        unsafe {
+            Ok(Self {
+                sensors_init: *lib.get(c"sensors_init")?,
+                sensors_cleanup: *lib.get(c"sensors_cleanup")?,
+                sensors_get_adapter_name: *lib.get(c"sensors_get_adapter_name")?,
+                sensors_get_label: *lib.get(c"sensors_get_label")?,
+                sensors_get_value: *lib.get(c"sensors_get_value")?,
+                sensors_set_value: *lib.get(c"sensors_set_value")?,
+                sensors_parse_chip_name: *lib.get(c"sensors_parse_chip_name")?,
+                sensors_free_chip_name: *lib.get(c"sensors_free_chip_name")?,
+                sensors_snprintf_chip_name: *lib.get(c"sensors_snprintf_chip_name")?,
+                sensors_get_detected_chips: *lib.get(c"sensors_get_detected_chips")?,
+                sensors_get_features: *lib.get(c"sensors_get_features")?,
+                sensors_get_all_subfeatures: *lib.get(c"sensors_get_all_subfeatures")?,
+                sensors_get_subfeature: *lib.get(c"sensors_get_subfeature")?,
+                sensors_do_chip_sets: *lib.get(c"sensors_do_chip_sets")?,
+                sensors_strerror: *lib.get(c"sensors_strerror")?,
+                free: *lib.get(c"free")?,
+                fopen: *lib.get(c"fopen")?,
+                fmemopen: *lib.get(c"fmemopen")?,
+                fclose: *lib.get(c"fclose")?,
+            })
+        }
+    }
+}
+
+#[cfg(feature = "link")]
+mod libc {
+    use std::ffi::{c_char, c_int, c_void};
+
+    use crate::ffi;
+
+    unsafe extern "C" {
+        pub fn free(ptr: *mut c_void);
+        pub fn fopen(path: *const c_char, mode: *const c_char) -> *mut ffi::FILE;
+        pub fn fmemopen(buf: *mut c_void, size: usize, mode: *const c_char) -> *mut ffi::FILE;
+        pub fn fclose(stream: *mut ffi::FILE) -> c_int;
+    }
+}
+
+#[cfg(feature = "link")]
+impl Functions {
+    /// The functions of the libsensors this crate was linked against.
+    pub(crate) const LINKED: Self = Self {
+        sensors_init: ffi::sensors_init,
+        sensors_cleanup: ffi::sensors_cleanup,
+        sensors_get_adapter_name: ffi::sensors_get_adapter_name,
+        sensors_get_label: ffi::sensors_get_label,
+        sensors_get_value: ffi::sensors_get_value,
+        sensors_set_value: ffi::sensors_set_value,
+        sensors_parse_chip_name: ffi::sensors_parse_chip_name,
+        sensors_free_chip_name: ffi::sensors_free_chip_name,
+        sensors_snprintf_chip_name: ffi::sensors_snprintf_chip_name,
+        sensors_get_detected_chips: ffi::sensors_get_detected_chips,
+        sensors_get_features: ffi::sensors_get_features,
+        sensors_get_all_subfeatures: ffi::sensors_get_all_subfeatures,
+        sensors_get_subfeature: ffi::sensors_get_subfeature,
+        sensors_do_chip_sets: ffi::sensors_do_chip_sets,
+        sensors_strerror: ffi::sensors_strerror,
+        free: libc::free,
+        fopen: libc::fopen,
+        fmemopen: libc::fmemopen,
+        fclose: libc::fclose,
+    };
+}
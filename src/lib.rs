@@ -1,25 +1,61 @@
-use std::{ffi::{c_char, c_double, c_int}, fmt::Display, os::raw::c_void, ptr, result::Result as StdResult, sync::atomic::{AtomicBool, Ordering as MemOrdering}};
-use libloading::{Library, Symbol};
+use std::{cell::Cell, ffi::{CString, c_char, c_int}, fmt::Display, io::{self, Read}, marker::PhantomData, mem::MaybeUninit, os::{raw::c_void, unix::ffi::OsStrExt}, path::{Path, PathBuf}, ptr, result::Result as StdResult, sync::atomic::{AtomicBool, Ordering as MemOrdering}};
+#[cfg(not(feature = "link"))]
+use libloading::Library;
 use log::warn;
-use crate::{error::SensorsError, utils::{GLibCFree, invert_res_opt, ptr_to_ref}};
+use crate::{config::{Config, ConfigFile, Diagnostic}, error::{ErrorKind, SensorsError}, functions::*, utils::{GLibCFree, checked_ptr_to_ref, invert_res_opt}};
 
 use self::error::{Error, Result};
 
+pub mod builder;
+pub mod callbacks;
 pub mod chip;
+pub mod chip_name;
+pub mod config;
+pub mod device;
 pub mod error;
 pub mod feature;
+pub mod shared;
+pub mod snapshot;
 pub mod subfeature;
+pub mod version;
+pub mod worker;
 mod ffi;
+mod functions;
 mod utils;
 
-pub use chip::{Chip, BusType, BusId};
+#[cfg(not(any(feature = "link", feature = "dlopen")))]
+compile_error!("either the `link` or the `dlopen` feature must be enabled");
+
+pub use builder::LibSensorsBuilder;
+pub use callbacks::ConfigParseError;
+pub use chip::{Chip, BusType, BusId, BusNr, BusPattern, SetsReport, SetStatement, SetFailure, FailedSet};
+pub use chip_name::{ChipName, ChipPattern};
+pub use device::DeviceInfo;
 pub use feature::Feature;
-pub use subfeature::{Subfeature, GenericSubfeature};
+pub use shared::SharedSensors;
+pub use worker::{SensorsWorker, SubfeatureSelector, Reading, Readings};
+pub use snapshot::{Snapshot, ChipInfo, FeatureInfo, SubfeatureInfo};
+pub use subfeature::{Subfeature, SubfeatureFlags, SubfeatureType, GenericSubfeature};
+pub use version::Version;
 
 #[derive(Debug)]
 pub enum LoadingError {
     Init(Error),
-    AlreadyInitialised
+    /// The configuration could not be opened or read.
+    Config(io::Error),
+    AlreadyInitialised,
+    /// libsensors is not installed, or not where it was looked for.
+    /// 
+    /// Contains every path that was tried together with the reason it could not be opened.
+    LibraryNotFound { tried: Vec<(PathBuf, String)> },
+    /// The loaded library is not a libsensors version the bindings were written for.
+    /// 
+    /// Contains the version it reported, or None if it did not report a valid one.
+    IncompatibleVersion { found: Option<Version> },
+    /// libsensors could not parse the configuration.
+    /// 
+    /// Contains every error it reported (these are also logged).
+    Parse(Vec<ConfigParseError>),
 }
 impl From<Error> for LoadingError {
     fn from(value: Error) -> Self {
@@ -29,27 +65,116 @@ impl From<Error> for LoadingError {
 impl Display for LoadingError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Init(e) => write!(f, "{e}"),
-            Self::AlreadyInitialised => write!(f, "Already initialised")
+            Self::Init(_) => write!(f, "Failed to initialise libsensors"),
+            Self::Config(_) => write!(f, "Failed to read configuration"),
+            Self::AlreadyInitialised => write!(f, "Already initialised"),
+            Self::LibraryNotFound { tried } => {
+                write!(f, "libsensors not found")?;
+                // The reasons from the dynamic linker already name the path.
+                for (i, (_, reason)) in tried.iter().enumerate() {
+                    write!(f, "{} {reason}", if i == 0 { ":" } else { ";" })?;
+                }
+                Ok(())
+            },
+            Self::IncompatibleVersion { found: Some(version) } => write!(f, "Incompatible libsensors version {version} (expected {}.x)", Version::SUPPORTED_MAJOR),
+            Self::IncompatibleVersion { found: None } => write!(f, "libsensors does not report a valid version"),
+            Self::Parse(errors) => {
+                write!(f, "Failed to parse configuration")?;
+                for (i, error) in errors.iter().enumerate() {
+                    write!(f, "{} {error}", if i == 0 { ":" } else { ";" })?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+impl std::error::Error for LoadingError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Init(e) => Some(e),
+            Self::Config(e) => Some(e),
+            _ => None,
         }
     }
 }
-impl std::error::Error for LoadingError { }
 
-static LIBSENSORS_DOES_NOT_EXIST: AtomicBool = AtomicBool::new(true);
-type LibLoadingResult<T> = StdResult<T, libloading::Error>;
-type SymbolResult<'lib, T> = LibLoadingResult<Symbol<'lib, T>>;
+/// Where the configuration passed to `sensors_init` comes from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// Let libsensors pick the system configuration (`/etc/sensors3.conf` and `/etc/sensors.d`).
+    #[default]
+    Default,
+    /// A configuration file.
+    Path(PathBuf),
+    /// The contents of a configuration file.
+    Memory(Vec<u8>),
+}
+impl ConfigSource {
+    /// Opens this configuration as a FILE* for reading.
+    /// 
+    /// Returns a null pointer for [`ConfigSource::Default`].
+    /// Any other pointer must be closed with fclose and, for [`ConfigSource::Memory`],
+    /// must not outlive self.
+    fn open(&self, fns: &Functions) -> StdResult<*mut ffi::FILE, LoadingError> {
+        let file = match self {
+            Self::Default => return Ok(ptr::null_mut()),
+            Self::Path(path) => {
+                let path = CString::new(path.as_os_str().as_bytes())
+                    .map_err(|e| LoadingError::Config(io::Error::new(io::ErrorKind::InvalidInput, e)))?;
+                // SAFETY: Both arguments are valid C-strings.
+                unsafe { (fns.fopen)(path.as_ptr(), c"r".as_ptr()) }
+            },
+            Self::Memory(buf) => {
+                // Older glibc versions refuse zero-sized buffers; an empty line is equivalent to an empty file.
+                let buf: &[u8] = if buf.is_empty() { b"\n" } else { buf };
+                // SAFETY: The stream is opened read-only, so fmemopen never writes through the pointer.
+                //  The caller guarantees the stream is closed before buf goes out of scope.
+                unsafe { (fns.fmemopen)(buf.as_ptr() as *mut c_void, buf.len(), c"r".as_ptr()) }
+            }
+        };
+        if file.is_null() {
+            Err(LoadingError::Config(io::Error::last_os_error()))
+        } else {
+            Ok(file)
+        }
+    }
+}
 
-pub(crate) type GetDetectedChips = unsafe extern "C" fn(*const ffi::sensors_chip_name, *mut c_int) -> *const ffi::sensors_chip_name;
-pub(crate) type GetFeatures = unsafe extern "C" fn(*const ffi::sensors_chip_name, *mut c_int) -> *const ffi::sensors_feature;
-pub(crate) type GetAllSubfeatures = unsafe extern "C" fn(*const ffi::sensors_chip_name, *const ffi::sensors_feature, *mut c_int) -> *const ffi::sensors_subfeature;
+/// The chips that appeared and disappeared during [`LibSensors::reload`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReloadDiff {
+    pub added: Vec<ChipName>,
+    pub removed: Vec<ChipName>,
+}
+impl ReloadDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+static LIBSENSORS_DOES_NOT_EXIST: AtomicBool = AtomicBool::new(true);
 
 /// A handle to an initialized libsensors environment.
 /// Note that only one of these may exist at the same time during the lifetime of a program!
 /// libsensors also makes no claims as to thread safety, so creating two instances in different threads is also forbidden!
+/// 
+/// For the same reason, this handle is [`Send`] but not [`Sync`]:
+/// it may be moved to another thread, but not used from several threads at once.
+/// Use [`SharedSensors`] to share it between threads.
 #[derive(Debug)]
 pub struct LibSensors {
-    inner: Library,
+    // Never used directly after loading, but fns is only valid as long as this lives.
+    #[cfg(not(feature = "link"))]
+    _inner: Library,
+    #[cfg(not(feature = "link"))]
+    fns: Functions,
+    version: Version,
+    /// The configuration libsensors was last initialised with.
+    config: ConfigSource,
+    /// The files of `config`, read right after libsensors read them.
+    config_files: Vec<ConfigFile>,
+    // libsensors is not thread-safe, so &LibSensors must not be shared between threads.
+    _not_sync: PhantomData<Cell<()>>,
 }
 impl LibSensors {
     /// Initialises Libsensors and returns a handle to it.
@@ -60,102 +185,391 @@ impl LibSensors {
     /// there is no guarantee that the second thread will not encounter a duplication error,
     /// even if their timings were perfect.
     /// If you do this, you should create proper synchronisation around the threads.
+    /// 
+    /// This uses the system configuration (`/etc/sensors3.conf` and `/etc/sensors.d`).
+    /// See [`Self::init_with_config`], [`Self::init_with_reader`] and [`Self::init_with_str`]
+    /// for using a different configuration.
     pub fn init() -> StdResult<Self, LoadingError> {
+        Self::init_with_source(ConfigSource::Default)
+    }
+
+    /// Initialises Libsensors using the configuration file at `path` instead of the system configuration.
+    /// 
+    /// The same restrictions as for [`Self::init`] apply.
+    pub fn init_with_config(path: impl AsRef<Path>) -> StdResult<Self, LoadingError> {
+        Self::init_with_source(ConfigSource::Path(path.as_ref().to_owned()))
+    }
+
+    /// Initialises Libsensors using a configuration read to its end from `reader`.
+    /// 
+    /// The same restrictions as for [`Self::init`] apply.
+    pub fn init_with_reader(mut reader: impl Read) -> StdResult<Self, LoadingError> {
+        let mut config = Vec::new();
+        reader.read_to_end(&mut config).map_err(LoadingError::Config)?;
+        Self::init_with_source(ConfigSource::Memory(config))
+    }
+
+    /// Initialises Libsensors using `config` as the contents of the configuration file.
+    /// 
+    /// The same restrictions as for [`Self::init`] apply.
+    pub fn init_with_str(config: &str) -> StdResult<Self, LoadingError> {
+        Self::init_with_source(ConfigSource::Memory(config.as_bytes().to_vec()))
+    }
+
+    /// Initialises Libsensors using the configuration from `config`,
+    /// which can also be a [`Config`] (e.g. made with a [`ConfigBuilder`](config::ConfigBuilder)).
+    /// 
+    /// The same restrictions as for [`Self::init`] apply.
+    pub fn init_with_source(config: impl Into<ConfigSource>) -> StdResult<Self, LoadingError> {
+        Self::builder().config(config).init()
+    }
+
+    /// Configures where libsensors is loaded from before initialising it.
+    pub fn builder() -> LibSensorsBuilder {
+        LibSensorsBuilder::new()
+    }
+
+    pub(crate) fn init_with_builder(builder: &LibSensorsBuilder) -> StdResult<Self, LoadingError> {
         // Acquire/Release is necessary here.
         // Acquire guarantees nobody stores, while we're reading.
         // Release guarantees nobody reads, while we're storing.
         if LIBSENSORS_DOES_NOT_EXIST.fetch_and(false, MemOrdering::AcqRel) {
-            unsafe { Library::new("libsensors.so.5") }
-                .map_err(Into::into)
-                .and_then(|inner| {
-                    SensorsError::convert_cint(
-                        unsafe { inner.get::<unsafe extern "C" fn(*mut c_void) -> c_int>(c"sensors_init")?(ptr::null_mut()) }
-                    ).map(|_| LibSensors { inner })
-                    .map_err(Into::into)
-                    // fetch_and above asserts that no two threads can be in this side of the if-stament at the same time.
-                    // Therefore we have guarantee, that at this point, LIBSENSORS_DOES_NOT_EXIST is false, so we can simply set it true.
-                    // (Using Relaxed here is fine, as we don't guarantee that this call succeeds, even if no LibSensors object exists)
-                    .inspect_err(|_| LIBSENSORS_DOES_NOT_EXIST.store(true, MemOrdering::Relaxed))
-                })
-                .map_err(LoadingError::Init)
+            Self::load(builder)
+                // fetch_and above asserts that no two threads can be in this side of the if-stament at the same time.
+                // Therefore we have guarantee, that at this point, LIBSENSORS_DOES_NOT_EXIST is false, so we can simply set it true.
+                // (Using Relaxed here is fine, as we don't guarantee that this call succeeds, even if no LibSensors object exists)
+                .inspect_err(|_| LIBSENSORS_DOES_NOT_EXIST.store(true, MemOrdering::Relaxed))
         } else {
             Err(LoadingError::AlreadyInitialised)
         }
     }
 
-    fn close_inner(&self) -> LibLoadingResult<()> {
-        unsafe { self.inner.get::<unsafe extern "C" fn()>(c"sensors_cleanup") }
-            .map(|f| unsafe { f() })
+    #[cfg(feature = "link")]
+    fn load(builder: &LibSensorsBuilder) -> StdResult<Self, LoadingError> {
+        // SAFETY: libsensors_version is a constant pointer to a static C-string.
+        let version = Self::check_version(unsafe { Version::from_raw(ffi::libsensors_version) })?;
+        // SAFETY: The singleton check in init_with_builder guarantees nobody else uses libsensors.
+        unsafe { callbacks::install() };
+        let config = builder.config_source().clone();
+        Self::sensors_init(&Functions::LINKED, &config)?;
+        Ok(LibSensors { version, config_files: config.read_files(), config, _not_sync: PhantomData })
+    }
+
+    #[cfg(not(feature = "link"))]
+    fn load(builder: &LibSensorsBuilder) -> StdResult<Self, LoadingError> {
+        let inner = Self::open_library(builder)?;
+        // SAFETY: inner is libsensors and fns is stored next to it, so it never outlives it.
+        // SAFETY: libsensors_version is a constant pointer to a static C-string.
+        //  The string is parsed before inner can go away.
+        let version = Self::check_version(
+            unsafe { inner.get::<*const *const c_char>(c"libsensors_version") }.ok()
+                .and_then(|version| unsafe { Version::from_raw(**version) })
+        )?;
+        // Only resolve the functions once we know their signatures match.
+        let fns = unsafe { Functions::load(&inner) }
+            .map_err(Error::from)?;
+        // SAFETY: inner was just loaded and the singleton check in init_with_builder guarantees nobody else uses it.
+        unsafe { callbacks::install(&inner) };
+        let config = builder.config_source().clone();
+        Self::sensors_init(&fns, &config)?;
+        Ok(LibSensors { _inner: inner, fns, version, config_files: config.read_files(), config, _not_sync: PhantomData })
+    }
+
+    /// Refuses versions whose struct layouts may not match the bindings.
+    fn check_version(version: Option<Version>) -> StdResult<Version, LoadingError> {
+        version.filter(Version::is_compatible)
+            .ok_or(LoadingError::IncompatibleVersion { found: version })
+    }
+
+    /// The version of the loaded libsensors.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// The configuration libsensors was initialised with (or last successfully reloaded with).
+    pub fn config_source(&self) -> &ConfigSource {
+        &self.config
+    }
+
+    pub(crate) fn config_files(&self) -> &[ConfigFile] {
+        &self.config_files
+    }
+
+    /// Whether the loaded libsensors knows buses of type `bus_type`.
+    /// 
+    /// Older versions never detect chips on unknown buses and refuse to format their names.
+    pub fn supports_bus_type(&self, bus_type: BusType) -> bool {
+        self.version >= bus_type.min_version()
+    }
+
+    /// Returns [`Error::Unsupported`] if `name` uses a bus type the loaded libsensors does not know.
+    pub(crate) fn check_bus_type(&self, name: &ChipName) -> Result<()> {
+        match name.bus_type() {
+            Some(bus_type) if !self.supports_bus_type(bus_type) => Err(Error::Unsupported {
+                what: bus_type.into(),
+                required: bus_type.min_version(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Opens the first of the builder's candidates that can be opened.
+    #[cfg(not(feature = "link"))]
+    fn open_library(builder: &LibSensorsBuilder) -> StdResult<Library, LoadingError> {
+        let mut tried = Vec::new();
+        for path in builder.candidates() {
+            // SAFETY: Loading libsensors runs no initialisers with safety requirements.
+            //  Whether the library actually is libsensors is checked by resolving its functions.
+            match unsafe { Library::new(&path) } {
+                Ok(lib) => return Ok(lib),
+                Err(e) => {
+                    // libloading only puts the reason reported by the dynamic linker in the source
+                    let reason = match std::error::Error::source(&e) {
+                        Some(source) => source.to_string(),
+                        None => e.to_string(),
+                    };
+                    tried.push((path, reason))
+                },
+            }
+        }
+        Err(LoadingError::LibraryNotFound { tried })
+    }
+
+    #[cfg(feature = "link")]
+    fn fns(&self) -> &Functions {
+        &Functions::LINKED
+    }
+
+    #[cfg(not(feature = "link"))]
+    fn fns(&self) -> &Functions {
+        &self.fns
+    }
+
+    /// Calls sensors_init with the given configuration.
+    /// 
+    /// Errors reported through the callbacks take precedence over the plain error code.
+    /// libsensors may report parse errors and still succeed, which is treated as a failure here.
+    fn sensors_init(fns: &Functions, config: &ConfigSource) -> StdResult<(), LoadingError> {
+        let file = config.open(fns)?;
+        // Anything left over is from a previous call and has already been handled.
+        callbacks::take_reports();
+        // SAFETY: file is either null (meaning the default configuration) or a FILE* opened for reading.
+        //  sensors_init parses the whole file during the call and does not keep the pointer around.
+        let res = unsafe { (fns.sensors_init)(file) };
+        if !file.is_null() {
+            // SAFETY: file was opened by ConfigSource::open and is closed exactly once.
+            unsafe { (fns.fclose)(file) };
+        }
+        let mut reports = callbacks::take_reports();
+        // libsensors only knows the names of the files it opened itself.
+        if let ConfigSource::Path(path) = config {
+            for error in &mut reports.parse {
+                error.file.get_or_insert_with(|| path.clone());
+            }
+        }
+        for error in &reports.parse {
+            warn!("libsensors configuration error: {error}");
+        }
+        if !reports.parse.is_empty() {
+            if res == 0 {
+                // libsensors only cleans up after itself when it fails.
+                // SAFETY: Nothing has been borrowed from this initialisation yet.
+                unsafe { (fns.sensors_cleanup)() };
+            }
+            return Err(LoadingError::Parse(reports.parse))
+        }
+        SensorsError::convert_cint(res)
+            .map_err(Error::from)?;
+        Ok(())
+    }
+
+    /// Reinitialises libsensors in place with `config`, rescanning for chips and rereading the configuration.
+    /// 
+    /// This picks up hotplugged devices and edited configuration files without giving up the handle.
+    /// Taking `&mut self` guarantees that no [`Chip`], [`Feature`] or [`Subfeature`] borrowed from
+    /// before the reload is still alive.
+    /// 
+    /// Returns which chips appeared and disappeared.
+    /// If reinitialisation fails, no chips are detected until the next successful reload.
+    pub fn reload(&mut self, config: impl Into<ConfigSource>) -> StdResult<ReloadDiff, LoadingError> {
+        let before = self.chip_names()?;
+        let config = config.into();
+        self.close_inner();
+        Self::sensors_init(self.fns(), &config)?;
+        self.config_files = config.read_files();
+        self.config = config;
+        let after = self.chip_names()?;
+        Ok(ReloadDiff {
+            added: after.iter().filter(|c| !before.contains(c)).cloned().collect(),
+            removed: before.into_iter().filter(|c| !after.contains(c)).collect(),
+        })
     }
 
-    pub fn close(self) -> LibLoadingResult<()> {
-        self.close_inner()
+    fn chip_names(&self) -> Result<Vec<ChipName>> {
+        self.get_chips()
+            .map(|chip| chip.map(|chip| chip.name()))
+            .collect()
+    }
+
+    fn close_inner(&self) {
+        // SAFETY: sensors_cleanup can be called at any time; everything borrowed from libsensors
+        //  is tied to &self, so nothing can still use what it frees once the caller is done with self.
+        unsafe { (self.fns().sensors_cleanup)() }
+    }
+
+    /// Cleans up libsensors. This is the same as dropping the handle.
+    pub fn close(self) { }
+
+    /// Copies every chip, feature and subfeature along with their current values into an owned [`Snapshot`].
+    /// 
+    /// Failing to read a value, chip or feature does not fail the snapshot;
+    /// the error is stored next to what could be read instead.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot::take(self, false)
+    }
+
+    /// Checks `config` against the chips detected by this instance (see [`Config::lint`]).
+    /// 
+    /// Only the names of chips, features and subfeatures are needed, so no values or labels are read.
+    /// Chips and features that cannot be enumerated are left out.
+    /// 
+    /// Features ignored by the configuration this instance was initialised with are not detected,
+    /// so to lint the system configuration, initialise with an empty one (e.g. `LibSensors::init_with_str("")`).
+    pub fn lint_config(&self, config: &Config, file: Option<&Path>) -> Vec<Diagnostic> {
+        config.lint(file, &Snapshot::take(self, true).chips)
+    }
+
+    /// Parses a chip name (e.g. `coretemp-isa-0000` or `*-pci-*`) using libsensors' own parser.
+    /// 
+    /// This is equivalent to [`ChipName::from_str`](std::str::FromStr::from_str).
+    pub fn parse_chip_name(&self, name: &str) -> Result<ChipName> {
+        let name = CString::new(name)
+            .map_err(|_| SensorsError::from(ErrorKind::ChipName))?;
+        let parse = self._sensors_parse_chip_name();
+        let free = self._sensors_free_chip_name();
+        let mut raw = MaybeUninit::<ffi::sensors_chip_name>::zeroed();
+        // SAFETY: name is a valid C-string and raw points to writable memory for a sensors_chip_name.
+        //  On failure, sensors_parse_chip_name frees everything it allocated itself.
+        SensorsError::convert_cint_with(self, unsafe { parse(name.as_ptr(), raw.as_mut_ptr()) })?;
+        // SAFETY: sensors_parse_chip_name succeeded, so raw is initialised (path stays zeroed)
+        //  and its prefix is either null or a C-string allocated by libsensors.
+        let mut raw = unsafe { raw.assume_init() };
+        let name = unsafe { ChipName::from_raw(&raw) };
+        // SAFETY: raw was filled by sensors_parse_chip_name and is freed exactly once.
+        unsafe { free(&mut raw) };
+        Ok(name)
+    }
+
+    /// Formats a chip name using libsensors.
+    /// 
+    /// Unlike [`ChipName`]'s [`Display`] implementation, this fails for names containing wildcards.
+    pub fn format_chip_name(&self, name: &ChipName) -> Result<String> {
+        self.check_bus_type(name)?;
+        let snprintf = self._sensors_snprintf_chip_name();
+        let raw = name.as_raw();
+        // SAFETY: snprintf may be called with a null buffer of size 0 to determine the required length.
+        //  raw only borrows from name, which outlives this function.
+        let len = SensorsError::convert_cint_with(self, unsafe { snprintf(ptr::null_mut(), 0, &raw) })? as usize;
+        let mut buf = vec![0u8; len + 1];
+        // SAFETY: buf has room for len characters and the NUL terminator.
+        SensorsError::convert_cint_with(self, unsafe { snprintf(buf.as_mut_ptr() as *mut c_char, buf.len(), &raw) })?;
+        buf.truncate(len);
+        Ok(String::from_utf8(buf).map_err(|e| e.utf8_error())?)
     }
 
     pub fn get_chip<'lib>(&'lib self, mut index: c_int) -> Result<Option<Chip<'lib>>> {
-        let fun = self._sensors_get_detected_chips()?;
+        let fun = self._sensors_get_detected_chips();
         let raw = unsafe { fun(ptr::null(), &mut index) };
         invert_res_opt(
-            unsafe { ptr_to_ref(raw) }.expect("get_chip: ptr not aligned")
+            unsafe { checked_ptr_to_ref(raw, "chip") }?
                 .map(|c| Chip::new(self, c))
         )
     }
 
-    pub fn get_chips<'lib>(&'lib self) -> Result<ChipIterator<'lib>> {
-        self._sensors_get_detected_chips()
-            .map(|s| ChipIterator::new(self, s))
-            .map_err(Into::into)
+    pub fn get_chips<'lib>(&'lib self) -> ChipIterator<'lib> {
+        ChipIterator::new(self, None)
+    }
+
+    /// Iterates over all detected chips matching `pattern` (e.g. `it87-*` or `*-isa-*`).
+    /// 
+    /// This selects the same chips as passing the pattern to the `sensors` command.
+    pub fn get_chips_matching<'lib>(&'lib self, pattern: &ChipPattern) -> ChipIterator<'lib> {
+        ChipIterator::new(self, Some(pattern.clone()))
+    }
+
+    /// Applies the `set` statements of the configuration to every detected chip matching `pattern`,
+    /// like `sensors -s`.
+    /// 
+    /// Returns one report per chip. See [`Chip::apply_sets`].
+    pub fn apply_sets(&self, pattern: &ChipPattern) -> Result<Vec<SetsReport>> {
+        self.get_chips_matching(pattern)
+            .map(|chip| chip.map(|chip| chip.apply_sets()))
+            .collect()
     }
 
     // -----------------------------------------
     //             Library functions
     // -----------------------------------------
 
-    pub(crate) fn _free(&self) -> SymbolResult<'_, GLibCFree> {
-        unsafe { self.inner.get(c"free") }
+    pub(crate) fn _free(&self) -> GLibCFree {
+        self.fns().free
+    }
+
+    pub(crate) fn _sensors_get_adapter_name(&self) -> GetAdapterName {
+        self.fns().sensors_get_adapter_name
+    }
+
+    pub(crate) fn _sensors_get_label(&self) -> GetLabel {
+        self.fns().sensors_get_label
+    }
+
+    pub(crate) fn _sensors_get_value(&self) -> GetValue {
+        self.fns().sensors_get_value
     }
 
-    pub(crate) fn _sensors_get_adapter_name(&self) -> SymbolResult<'_, unsafe extern "C" fn(*const ffi::sensors_bus_id) -> *const c_char> {
-        unsafe { self.inner.get(c"sensors_get_adapter_name") }
+    pub(crate) fn _sensors_set_value(&self) -> SetValue {
+        self.fns().sensors_set_value
     }
 
-    pub(crate) fn _sensors_get_label(&self) -> SymbolResult<'_, unsafe extern "C" fn(*const ffi::sensors_chip_name, *const ffi::sensors_feature) -> *mut c_char> { 
-        unsafe { self.inner.get(c"sensors_get_label") }
+    pub(crate) fn _sensors_parse_chip_name(&self) -> ParseChipName {
+        self.fns().sensors_parse_chip_name
     }
 
-    pub(crate) fn _sensors_get_value(&self) -> SymbolResult<'_, unsafe extern "C" fn(*const ffi::sensors_chip_name, c_int, *mut c_double) -> c_int> {
-        unsafe { self.inner.get(c"sensors_get_value") }
+    pub(crate) fn _sensors_free_chip_name(&self) -> FreeChipName {
+        self.fns().sensors_free_chip_name
     }
 
-    pub(crate) fn _sensors_set_value(&self) -> SymbolResult<'_, unsafe extern "C" fn(*const ffi::sensors_chip_name, c_int, c_double) -> c_int> {
-        unsafe { self.inner.get(c"sensors_set_value") }
+    pub(crate) fn _sensors_snprintf_chip_name(&self) -> SnprintfChipName {
+        self.fns().sensors_snprintf_chip_name
     }
 
-    pub(crate) fn _sensors_get_detected_chips(&self) -> SymbolResult<'_, GetDetectedChips> {
-        unsafe { self.inner.get(c"sensors_get_detected_chips") }
+    pub(crate) fn _sensors_get_detected_chips(&self) -> GetDetectedChips {
+        self.fns().sensors_get_detected_chips
     }
 
-    pub(crate) fn _sensors_get_features(&self) -> SymbolResult<'_, GetFeatures> {
-        unsafe { self.inner.get(c"sensors_get_features") }
+    pub(crate) fn _sensors_get_features(&self) -> GetFeatures {
+        self.fns().sensors_get_features
     }
 
-    pub(crate) fn _sensors_get_all_subfeatures(&self) -> SymbolResult<'_, GetAllSubfeatures> {
-        unsafe { self.inner.get(c"sensors_get_all_subfeatures") }
+    pub(crate) fn _sensors_get_all_subfeatures(&self) -> GetAllSubfeatures {
+        self.fns().sensors_get_all_subfeatures
     }
 
-    pub(crate) fn _sensors_get_subfeature(&self) -> SymbolResult<'_, unsafe extern "C" fn(*const ffi::sensors_chip_name, *const ffi::sensors_feature, ffi::sensors_subfeature_type::Type) -> *const ffi::sensors_subfeature> {
-        unsafe { self.inner.get(c"sensors_get_subfeature") }
+    pub(crate) fn _sensors_get_subfeature(&self) -> GetSubfeature {
+        self.fns().sensors_get_subfeature
     }
 
-    pub(crate) fn _sensors_strerror(&self) -> SymbolResult<'_, unsafe extern "C" fn(c_int) -> *const c_char> {
-        unsafe { self.inner.get(c"sensors_strerror") }
+    pub(crate) fn _sensors_do_chip_sets(&self) -> DoChipSets {
+        self.fns().sensors_do_chip_sets
+    }
+
+    pub(crate) fn _sensors_strerror(&self) -> Strerror {
+        self.fns().sensors_strerror
     }
 }
 impl Drop for LibSensors {
     fn drop(&mut self) {
-        if let Err(e) = self.close_inner() {
-            warn!("Failed to load sensors_cleanup: {e}")
-        }
+        self.close_inner();
         // Given that we've previously ensured that only one instance of this exists,
         // this must necessarily be true on Drop as well.
         // Therefore, if anyone tries to call LibSensors::init before this line,
@@ -168,20 +582,25 @@ impl Drop for LibSensors {
 
 pub struct ChipIterator<'lib> {
     lib: &'lib LibSensors,
-    // loading the GetDetectedChips eagerly means we don't have to worry about LibLoading errors during iteration.
-    fun: Symbol<'lib, GetDetectedChips>,
+    // owned, so that the raw match passed to libsensors can borrow from it
+    pattern: Option<ChipPattern>,
     index: c_int
 }
 impl<'lib> ChipIterator<'lib> {
-    fn new(lib: &'lib LibSensors, fun: Symbol<'lib, GetDetectedChips>) -> Self {
-        ChipIterator { lib, fun, index: 0 }
+    fn new(lib: &'lib LibSensors, pattern: Option<ChipPattern>) -> Self {
+        ChipIterator { lib, pattern, index: 0 }
     }
 }
 impl<'lib> Iterator for ChipIterator<'lib> {
     type Item = Result<Chip<'lib>>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let ptr = unsafe { (self.fun)(ptr::null(), &mut self.index) };
-        unsafe { ptr_to_ref(ptr) }.unwrap().map(|c| Chip::new(self.lib, c))
+        let pattern = self.pattern.as_ref().map(ChipPattern::as_raw);
+        let match_ = pattern.as_ref().map_or(ptr::null(), |p| p as *const _);
+        // SAFETY: match_ is either null or borrows from self.pattern, which outlives this call.
+        let ptr = unsafe { (self.lib._sensors_get_detected_chips())(match_, &mut self.index) };
+        unsafe { checked_ptr_to_ref(ptr, "chip") }
+            .transpose()
+            .map(|c| c.and_then(|c| Chip::new(self.lib, c)))
     }
 }
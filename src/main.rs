@@ -1,18 +1,61 @@
-use libsensors_rs::LibSensors;
+use std::{env, fs, path::PathBuf, process::ExitCode};
 
+use libsensors_rs::{LibSensors, config::{self, Config}};
+
+
+fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let mut args = env::args_os().skip(1);
+    if args.next().is_some_and(|arg| arg == "lint") {
+        return lint(args.map(PathBuf::from).collect())
+    }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
     let lib = LibSensors::init().unwrap();
-    for chip in lib.get_chips().unwrap() {
+    for chip in lib.get_chips() {
         let chip = chip?;
-        println!("C: {} ({:?})", chip.get_name_raw()?.unwrap().to_str()?, chip.get_prefix());
-        for feature in chip.get_features()? {
+        println!("C: {} ({:?})", chip.get_name_raw().unwrap().to_str()?, chip.get_prefix());
+        for feature in chip.get_features() {
             let feature = feature?;
-            println!("  F: {} ({:?})", feature.get_label().unwrap(), feature.get_name());
-            for subfeature in feature.get_subfeatures()? {
+            println!("  F: {} ({:?})", feature.get_label().unwrap(), feature.get_name().unwrap());
+            for subfeature in feature.get_subfeatures() {
+                let subfeature = subfeature?;
                 println!("    {:?}", subfeature.get_name().unwrap())
             }
         }
     }
-    Ok(())
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `lint [FILE]...`: checks configuration files against the detected chips.
+/// Without files, checks the system configuration, i.e. the files libsensors reads by default.
+fn lint(mut files: Vec<PathBuf>) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    if files.is_empty() {
+        files = config::system_files();
+    }
+
+    // Without a configuration nothing is ignored, so every feature is visible.
+    let lib = LibSensors::init_with_str("")?;
+    let mut problems = 0;
+    for file in &files {
+        let src = match fs::read_to_string(file) {
+            Ok(src) => src,
+            Err(e) => {
+                println!("{}: {e}", file.display());
+                problems += 1;
+                continue
+            }
+        };
+        let config = match Config::parse(&src) {
+            Ok(config) => config,
+            Err(e) => {
+                println!("{}:{}: {}", file.display(), e.span.line, e.message);
+                problems += 1;
+                continue
+            }
+        };
+        for diagnostic in lib.lint_config(&config, Some(file)) {
+            println!("{diagnostic}");
+            problems += 1;
+        }
+    }
+    Ok(if problems == 0 { ExitCode::SUCCESS } else { ExitCode::FAILURE })
 }
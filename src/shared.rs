@@ -0,0 +1,48 @@
+use std::{result::Result as StdResult, sync::{Arc, Mutex, MutexGuard}};
+
+use crate::{LibSensors, LoadingError, Snapshot};
+
+/// A [`LibSensors`] handle that can be shared between threads.
+///
+/// Every access goes through a mutex, so libsensors is never called from two threads at once.
+/// Cloning this creates another reference to the same handle.
+#[derive(Debug, Clone)]
+pub struct SharedSensors {
+    inner: Arc<Mutex<LibSensors>>,
+}
+impl SharedSensors {
+    pub fn new(lib: LibSensors) -> Self {
+        Self { inner: Arc::new(Mutex::new(lib)) }
+    }
+
+    /// Initialises libsensors (see [`LibSensors::init`]) and wraps it for sharing.
+    pub fn init() -> StdResult<Self, LoadingError> {
+        LibSensors::init().map(Self::new)
+    }
+
+    /// Locks the handle for exclusive use by the current thread.
+    ///
+    /// Anything borrowed from the guard (e.g. a [`crate::Chip`]) must be dropped before it.
+    pub fn lock(&self) -> MutexGuard<'_, LibSensors> {
+        // A panic while holding the lock cannot leave libsensors in an inconsistent state,
+        // because every call into it is atomic from Rust's point of view.
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Runs `f` with exclusive access to the handle.
+    pub fn with<R>(&self, f: impl FnOnce(&LibSensors) -> R) -> R {
+        f(&self.lock())
+    }
+
+    /// Takes a [`Snapshot`] while holding the lock.
+    pub fn snapshot(&self) -> Snapshot {
+        self.with(LibSensors::snapshot)
+    }
+
+    /// Returns the handle if this is the last reference to it.
+    pub fn try_into_inner(self) -> StdResult<LibSensors, Self> {
+        Arc::try_unwrap(self.inner)
+            .map(|mutex| mutex.into_inner().unwrap_or_else(|e| e.into_inner()))
+            .map_err(|inner| Self { inner })
+    }
+}
@@ -0,0 +1,154 @@
+use std::{ffi::{c_double, c_int}, path::PathBuf, sync::Arc};
+
+use crate::{BusId, Chip, ChipName, Feature, LibSensors, Subfeature, SubfeatureFlags, SubfeatureType, error::{Error, Result}, feature::FeatureType};
+
+/// Splits `items` into the successful ones and the errors, which are kept for the caller to inspect.
+fn partition<T>(items: impl Iterator<Item = Result<T>>) -> (Vec<T>, Vec<Arc<Error>>) {
+    let mut ok = Vec::new();
+    let mut errors = Vec::new();
+    for item in items {
+        match item {
+            Ok(item) => ok.push(item),
+            Err(e) => errors.push(Arc::new(e)),
+        }
+    }
+    (ok, errors)
+}
+
+/// An owned copy of the whole sensor tree, including the values at the time it was taken.
+///
+/// Unlike [`Chip`], [`Feature`] and [`Subfeature`], this does not borrow [`LibSensors`],
+/// so it can be stored, sent to other threads and kept after the library is closed.
+///
+/// Chips, features and subfeatures that cannot be read do not fail the snapshot.
+/// Their errors are stored next to the parts that could be read instead.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub chips: Vec<ChipInfo>,
+    /// Why chips were left out (e.g. because libsensors reported an unknown bus type).
+    pub errors: Vec<Arc<Error>>,
+}
+impl Snapshot {
+    /// Copies the sensor tree. With `names_only`, values and labels are left out, so nothing is read from sysfs.
+    pub(crate) fn take(lib: &LibSensors, names_only: bool) -> Self {
+        let (chips, errors) = partition(lib.get_chips().map(|chip| chip.map(|chip| ChipInfo::new(&chip, names_only))));
+        Self { chips, errors }
+    }
+
+    /// Finds the chip with the given name (e.g. `coretemp-isa-0000`).
+    ///
+    /// Use [`ChipName::matches`] to look chips up by a pattern instead.
+    pub fn chip(&self, name: &ChipName) -> Option<&ChipInfo> {
+        self.chips.iter().find(|chip| &chip.name == name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ChipInfo {
+    pub name: ChipName,
+    /// The name of the adapter the chip is connected to.
+    pub adapter: Option<String>,
+    pub bus: BusId,
+    pub address: c_int,
+    pub sysfs_path: Option<PathBuf>,
+    pub features: Vec<FeatureInfo>,
+    /// Why features were left out (e.g. because their type is unknown to this crate).
+    pub errors: Vec<Arc<Error>>,
+}
+impl ChipInfo {
+    fn new(chip: &Chip<'_>, names_only: bool) -> Self {
+        let (features, errors) = partition(chip.get_features().map(|feature| feature.map(|feature| FeatureInfo::new(&feature, names_only))));
+        Self {
+            name: chip.name(),
+            // Adapter names are informational, so a name that is not UTF-8 is not worth an error.
+            adapter: chip.get_name_raw().map(|name| name.to_string_lossy().into_owned()),
+            bus: chip.get_bus_id(),
+            address: chip.get_address(),
+            sysfs_path: chip.sysfs_path().map(|path| path.to_owned()),
+            features,
+            errors,
+        }
+    }
+
+    /// Finds the feature with the given name (e.g. `temp1`).
+    pub fn feature(&self, name: &str) -> Option<&FeatureInfo> {
+        self.features.iter().find(|feature| feature.name == name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FeatureInfo {
+    pub name: String,
+    pub number: c_int,
+    /// The label of the feature, or None if it could not be retrieved (or only the names were copied).
+    pub label: Option<String>,
+    pub type_: FeatureType,
+    pub subfeatures: Vec<SubfeatureInfo>,
+    /// Why subfeatures were left out.
+    pub errors: Vec<Arc<Error>>,
+}
+impl FeatureInfo {
+    fn new(feature: &Feature<'_>, names_only: bool) -> Self {
+        let (subfeatures, errors) = partition(feature.get_subfeatures().map(|subfeature| subfeature.map(|subfeature| SubfeatureInfo::new(&subfeature, names_only))));
+        Self {
+            name: feature.get_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default(),
+            number: feature.number(),
+            label: if names_only { None } else { feature.get_label().ok() },
+            type_: feature.get_type(),
+            subfeatures,
+            errors,
+        }
+    }
+
+    /// Finds the subfeature with the given name (e.g. `temp1_input`).
+    pub fn subfeature(&self, name: &str) -> Option<&SubfeatureInfo> {
+        self.subfeatures.iter().find(|subfeature| subfeature.name == name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SubfeatureInfo {
+    pub name: String,
+    pub number: c_int,
+    pub type_: SubfeatureType,
+    pub flags: SubfeatureFlags,
+    /// The value read when the snapshot was taken, or None if the subfeature is not readable
+    /// (or only the names were copied).
+    pub value: Option<std::result::Result<c_double, Arc<Error>>>,
+}
+impl SubfeatureInfo {
+    fn new(subfeature: &Subfeature<'_>, names_only: bool) -> Self {
+        Self {
+            name: subfeature.get_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default(),
+            number: subfeature.number(),
+            type_: subfeature.get_type(),
+            flags: subfeature.flags(),
+            value: (!names_only && subfeature.can_get()).then(|| subfeature.get_value().map_err(Arc::new)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BusType, ffi::{sensors_bus_id, sensors_chip_name}};
+
+    use super::*;
+
+    #[test]
+    fn chip_finds_parsed_names() {
+        // Detected ISA chips have bus number 0, parsed names used to have "any".
+        let parsed: ChipName = "coretemp-isa-0000".parse().unwrap();
+        let raw = sensors_chip_name { bus: sensors_bus_id { type_: BusType::ISA as _, nr: 0 }, ..parsed.as_raw() };
+        let chip = ChipInfo {
+            name: unsafe { ChipName::from_raw(&raw) },
+            adapter: Some("ISA adapter".to_owned()),
+            bus: BusId { type_: BusType::ISA, nr: 0 },
+            address: 0,
+            sysfs_path: None,
+            features: Vec::new(),
+            errors: Vec::new(),
+        };
+        let snapshot = Snapshot { chips: vec![chip], errors: Vec::new() };
+        assert!(snapshot.chip(&parsed).is_some());
+    }
+}
@@ -1,17 +1,34 @@
-use std::ffi::{CStr, c_double};
+use std::ffi::{CStr, c_double, c_int, c_uint};
 
-use crate::{LibSensors, error::{Result, SensorsError}, feature::FeatureType, ffi::{self, sensors_chip_name, sensors_subfeature}};
+use bitflags::bitflags;
+
+use crate::{ChipName, LibSensors, chip::get_feature_raw, error::{ErrorContext, Operation, Result, SensorsError}, feature::{Feature, FeatureType}, ffi::{self, sensors_chip_name, sensors_feature, sensors_subfeature, sensors_subfeature_type::*}, utils::{invert_res_opt, try_cstr}};
 
 
 #[derive(Debug)]
 pub struct Subfeature<'lib> {
     lib: &'lib LibSensors,
     chip: &'lib sensors_chip_name,
+    feature: &'lib sensors_feature,
     raw: &'lib sensors_subfeature
 }
 impl<'lib> Subfeature<'lib> {
-    pub(crate) fn new(raw: &'lib sensors_subfeature, chip: &'lib sensors_chip_name, lib: &'lib LibSensors) -> Self {
-        Self { lib, chip, raw }
+    pub(crate) fn new(raw: &'lib sensors_subfeature, feature: &'lib sensors_feature, chip: &'lib sensors_chip_name, lib: &'lib LibSensors) -> Self {
+        Self { lib, chip, feature, raw }
+    }
+
+    /// Describes this subfeature for errors.
+    pub(crate) fn error_context(&self) -> ErrorContext {
+        let lossy = |ptr| unsafe { try_cstr(ptr) }.map(|c| c.to_string_lossy().into_owned());
+        ErrorContext {
+            // SAFETY: Subfeatures are only created from a Feature of a Chip,
+            //  so the prefix of self.chip has been checked in Chip::new.
+            chip: Some(unsafe { ChipName::from_raw(self.chip) }),
+            // SAFETY: Names of features and subfeatures are C-strings owned by libsensors.
+            feature: lossy(self.feature.name),
+            subfeature: lossy(self.raw.name),
+            operation: None,
+        }
     }
 
     pub fn get_name(&self) -> Option<&CStr> {
@@ -24,10 +41,15 @@ impl<'lib> Subfeature<'lib> {
     }
 
     pub fn get_value(&self) -> Result<c_double> {
-        let fun = self.lib._sensors_get_value()?;
+        self.get_value_inner()
+            .map_err(|e| e.with_context(self.error_context().with_operation(Operation::Get)))
+    }
+
+    fn get_value_inner(&self) -> Result<c_double> {
+        let fun = self.lib._sensors_get_value();
         
         let mut value: c_double = c_double::NAN;
-        SensorsError::convert_cint(
+        SensorsError::convert_cint_with(self.lib,
             // SAFETY: I dunno what to say, there aren't really any concerns here.
             //  *mut c_double isn't stored anywhere, self.chip exists so long as this struct instance does.
             unsafe { fun(self.chip, self.raw.number, &mut value) }
@@ -36,69 +58,303 @@ impl<'lib> Subfeature<'lib> {
     }
 
     pub fn set_value(&self, value: c_double) -> Result<()> {
-        let fun = self.lib._sensors_set_value()?;
-        SensorsError::convert_cint(
+        self.set_value_inner(value)
+            .map_err(|e| e.with_context(self.error_context().with_operation(Operation::Set)))
+    }
+
+    fn set_value_inner(&self, value: c_double) -> Result<()> {
+        let fun = self.lib._sensors_set_value();
+        SensorsError::convert_cint_with(self.lib,
             unsafe { fun(self.chip, self.raw.number, value) }
         )?;
         Ok(())
     }
 
+    /// The type of this subfeature.
+    /// 
+    /// Types unknown to this crate are reported as [`SubfeatureType::Unknown`].
+    pub fn get_type(&self) -> SubfeatureType {
+        SubfeatureType::from_repr(self.raw.type_).unwrap_or(SubfeatureType::Unknown)
+    }
+
+    /// The number of this subfeature, which is unique and stable within its chip.
+    pub fn number(&self) -> c_int {
+        self.raw.number
+    }
+
+    pub fn flags(&self) -> SubfeatureFlags {
+        SubfeatureFlags::from_bits_retain(self.raw.flags)
+    }
+
     pub fn can_get(&self) -> bool { 
-        self.raw.flags & ffi::SENSORS_MODE_R != 0
+        self.flags().contains(SubfeatureFlags::READ)
     }
 
     pub fn can_set(&self) -> bool {
-        self.raw.flags & ffi::SENSORS_MODE_W != 0
+        self.flags().contains(SubfeatureFlags::WRITE)
+    }
+
+    /// Whether the value of this subfeature is affected by a `compute` statement of its feature.
+    pub fn is_computed(&self) -> bool {
+        self.flags().contains(SubfeatureFlags::COMPUTE_MAPPING)
+    }
+
+    /// The feature this subfeature belongs to (and whose `compute` statement applies to it).
+    pub fn mapping(&self) -> Result<Option<Feature<'lib>>> {
+        // Features are numbered by their position, so the mapping can be used as an index.
+        invert_res_opt(unsafe { get_feature_raw(self.lib, self.chip, &mut self.raw.mapping.clone()) })
+            .map(|f| f.filter(|f| f.number() == self.raw.mapping))
+    }
+}
+
+bitflags! {
+    /// The flags of a subfeature (`SENSORS_MODE_*` and `SENSORS_COMPUTE_MAPPING`).
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct SubfeatureFlags: c_uint {
+        /// The value can be read.
+        const READ = ffi::SENSORS_MODE_R;
+        /// The value can be written.
+        const WRITE = ffi::SENSORS_MODE_W;
+        /// The value is affected by the `compute` statement of the feature it maps to.
+        const COMPUTE_MAPPING = ffi::SENSORS_COMPUTE_MAPPING;
     }
 }
 
 
+/// The type of a subfeature.
+/// 
+/// Every subfeature type belongs to exactly one [`FeatureType`], see [`SubfeatureType::feature_type`].
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, strum::FromRepr)]
+pub enum SubfeatureType {
+    InInput = SENSORS_SUBFEATURE_IN_INPUT,
+    InMin = SENSORS_SUBFEATURE_IN_MIN,
+    InMax = SENSORS_SUBFEATURE_IN_MAX,
+    InLCrit = SENSORS_SUBFEATURE_IN_LCRIT,
+    InCrit = SENSORS_SUBFEATURE_IN_CRIT,
+    InAverage = SENSORS_SUBFEATURE_IN_AVERAGE,
+    InLowest = SENSORS_SUBFEATURE_IN_LOWEST,
+    InHighest = SENSORS_SUBFEATURE_IN_HIGHEST,
+    InAlarm = SENSORS_SUBFEATURE_IN_ALARM,
+    InMinAlarm = SENSORS_SUBFEATURE_IN_MIN_ALARM,
+    InMaxAlarm = SENSORS_SUBFEATURE_IN_MAX_ALARM,
+    InBeep = SENSORS_SUBFEATURE_IN_BEEP,
+    InLCritAlarm = SENSORS_SUBFEATURE_IN_LCRIT_ALARM,
+    InCritAlarm = SENSORS_SUBFEATURE_IN_CRIT_ALARM,
+    FanInput = SENSORS_SUBFEATURE_FAN_INPUT,
+    FanMin = SENSORS_SUBFEATURE_FAN_MIN,
+    FanMax = SENSORS_SUBFEATURE_FAN_MAX,
+    FanAlarm = SENSORS_SUBFEATURE_FAN_ALARM,
+    FanFault = SENSORS_SUBFEATURE_FAN_FAULT,
+    FanDiv = SENSORS_SUBFEATURE_FAN_DIV,
+    FanBeep = SENSORS_SUBFEATURE_FAN_BEEP,
+    FanPulses = SENSORS_SUBFEATURE_FAN_PULSES,
+    FanMinAlarm = SENSORS_SUBFEATURE_FAN_MIN_ALARM,
+    FanMaxAlarm = SENSORS_SUBFEATURE_FAN_MAX_ALARM,
+    TempInput = SENSORS_SUBFEATURE_TEMP_INPUT,
+    TempMax = SENSORS_SUBFEATURE_TEMP_MAX,
+    TempMaxHyst = SENSORS_SUBFEATURE_TEMP_MAX_HYST,
+    TempMin = SENSORS_SUBFEATURE_TEMP_MIN,
+    TempCrit = SENSORS_SUBFEATURE_TEMP_CRIT,
+    TempCritHyst = SENSORS_SUBFEATURE_TEMP_CRIT_HYST,
+    TempLCrit = SENSORS_SUBFEATURE_TEMP_LCRIT,
+    TempEmergency = SENSORS_SUBFEATURE_TEMP_EMERGENCY,
+    TempEmergencyHyst = SENSORS_SUBFEATURE_TEMP_EMERGENCY_HYST,
+    TempLowest = SENSORS_SUBFEATURE_TEMP_LOWEST,
+    TempHighest = SENSORS_SUBFEATURE_TEMP_HIGHEST,
+    TempMinHyst = SENSORS_SUBFEATURE_TEMP_MIN_HYST,
+    TempLCritHyst = SENSORS_SUBFEATURE_TEMP_LCRIT_HYST,
+    TempAlarm = SENSORS_SUBFEATURE_TEMP_ALARM,
+    TempMaxAlarm = SENSORS_SUBFEATURE_TEMP_MAX_ALARM,
+    TempMinAlarm = SENSORS_SUBFEATURE_TEMP_MIN_ALARM,
+    TempCritAlarm = SENSORS_SUBFEATURE_TEMP_CRIT_ALARM,
+    TempFault = SENSORS_SUBFEATURE_TEMP_FAULT,
+    TempType = SENSORS_SUBFEATURE_TEMP_TYPE,
+    TempOffset = SENSORS_SUBFEATURE_TEMP_OFFSET,
+    TempBeep = SENSORS_SUBFEATURE_TEMP_BEEP,
+    TempEmergencyAlarm = SENSORS_SUBFEATURE_TEMP_EMERGENCY_ALARM,
+    TempLCritAlarm = SENSORS_SUBFEATURE_TEMP_LCRIT_ALARM,
+    PowerAverage = SENSORS_SUBFEATURE_POWER_AVERAGE,
+    PowerAverageHighest = SENSORS_SUBFEATURE_POWER_AVERAGE_HIGHEST,
+    PowerAverageLowest = SENSORS_SUBFEATURE_POWER_AVERAGE_LOWEST,
+    PowerInput = SENSORS_SUBFEATURE_POWER_INPUT,
+    PowerInputHighest = SENSORS_SUBFEATURE_POWER_INPUT_HIGHEST,
+    PowerInputLowest = SENSORS_SUBFEATURE_POWER_INPUT_LOWEST,
+    PowerCap = SENSORS_SUBFEATURE_POWER_CAP,
+    PowerCapHyst = SENSORS_SUBFEATURE_POWER_CAP_HYST,
+    PowerMax = SENSORS_SUBFEATURE_POWER_MAX,
+    PowerCrit = SENSORS_SUBFEATURE_POWER_CRIT,
+    PowerMin = SENSORS_SUBFEATURE_POWER_MIN,
+    PowerLCrit = SENSORS_SUBFEATURE_POWER_LCRIT,
+    PowerAverageInterval = SENSORS_SUBFEATURE_POWER_AVERAGE_INTERVAL,
+    PowerAlarm = SENSORS_SUBFEATURE_POWER_ALARM,
+    PowerCapAlarm = SENSORS_SUBFEATURE_POWER_CAP_ALARM,
+    PowerMaxAlarm = SENSORS_SUBFEATURE_POWER_MAX_ALARM,
+    PowerCritAlarm = SENSORS_SUBFEATURE_POWER_CRIT_ALARM,
+    PowerMinAlarm = SENSORS_SUBFEATURE_POWER_MIN_ALARM,
+    PowerLCritAlarm = SENSORS_SUBFEATURE_POWER_LCRIT_ALARM,
+    EnergyInput = SENSORS_SUBFEATURE_ENERGY_INPUT,
+    CurrentInput = SENSORS_SUBFEATURE_CURR_INPUT,
+    CurrentMin = SENSORS_SUBFEATURE_CURR_MIN,
+    CurrentMax = SENSORS_SUBFEATURE_CURR_MAX,
+    CurrentLCrit = SENSORS_SUBFEATURE_CURR_LCRIT,
+    CurrentCrit = SENSORS_SUBFEATURE_CURR_CRIT,
+    CurrentAverage = SENSORS_SUBFEATURE_CURR_AVERAGE,
+    CurrentLowest = SENSORS_SUBFEATURE_CURR_LOWEST,
+    CurrentHighest = SENSORS_SUBFEATURE_CURR_HIGHEST,
+    CurrentAlarm = SENSORS_SUBFEATURE_CURR_ALARM,
+    CurrentMinAlarm = SENSORS_SUBFEATURE_CURR_MIN_ALARM,
+    CurrentMaxAlarm = SENSORS_SUBFEATURE_CURR_MAX_ALARM,
+    CurrentBeep = SENSORS_SUBFEATURE_CURR_BEEP,
+    CurrentLCritAlarm = SENSORS_SUBFEATURE_CURR_LCRIT_ALARM,
+    CurrentCritAlarm = SENSORS_SUBFEATURE_CURR_CRIT_ALARM,
+    HumidityInput = SENSORS_SUBFEATURE_HUMIDITY_INPUT,
+    Vid = SENSORS_SUBFEATURE_VID,
+    IntrusionAlarm = SENSORS_SUBFEATURE_INTRUSION_ALARM,
+    IntrusionBeep = SENSORS_SUBFEATURE_INTRUSION_BEEP,
+    BeepEnable = SENSORS_SUBFEATURE_BEEP_ENABLE,
+    Unknown = SENSORS_SUBFEATURE_UNKNOWN,
+}
+impl SubfeatureType {
+    /// The type of feature this subfeature type belongs to,
+    /// or None for [`SubfeatureType::Unknown`].
+    pub fn feature_type(self) -> Option<FeatureType> {
+        FeatureType::from_repr(self as u32 >> 8)
+    }
+}
+
 /// Feature-independent enum for commonly used subtypes
 #[non_exhaustive]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum GenericSubfeature {
+    /// The current value of the feature.
     Input,
+    /// Lower limit.
     Min,
+    /// Upper limit.
     Max,
+    /// Critical lower limit.
+    LCrit,
+    /// Critical upper limit.
+    Crit,
+    /// Emergency upper limit.
+    Emergency,
+    /// Average value.
+    Average,
+    /// Lowest value seen so far.
+    Lowest,
+    /// Highest value seen so far.
+    Highest,
+    /// Any alarm is raised.
+    Alarm,
+    /// The lower limit was crossed.
+    MinAlarm,
+    /// The upper limit was crossed.
+    MaxAlarm,
+    /// The critical upper limit was crossed.
+    CritAlarm,
+    /// The sensor is faulty (e.g. a disconnected fan or diode).
+    Fault,
+    /// Whether an alarm beeps.
+    Beep,
+    /// Offset added to the value.
+    Offset,
+    /// Hysteresis of the upper limit (the cap for power features).
+    Hysteresis,
 }
 impl GenericSubfeature {
+    /// The concrete subfeature type for this role on a feature of type `feature_type`,
+    /// or None if such features have no subfeature for this role.
+    pub fn to_subfeature_type(self, feature_type: FeatureType) -> Option<SubfeatureType> {
+        GENERIC_SUBFEATURES.iter()
+            .find(|(generic, feature, _)| *generic == self && *feature == feature_type)
+            .map(|(_, _, subfeature)| *subfeature)
+    }
+
     pub fn to_primitive(self, feature_type: FeatureType) -> Option<ffi::sensors_subfeature_type::Type> {
-        use ffi::sensors_subfeature_type::*;
-        Some(match self {
-            // can you see me doing this for every possible subfeature?
-            // no. no you cannot. because i will not.
-            Self::Input => {
-                match feature_type {
-                    FeatureType::In => SENSORS_SUBFEATURE_IN_INPUT,
-                    FeatureType::Fan => SENSORS_SUBFEATURE_FAN_INPUT,
-                    FeatureType::Temp => SENSORS_SUBFEATURE_TEMP_INPUT,
-                    FeatureType::Power => SENSORS_SUBFEATURE_POWER_INPUT,
-                    FeatureType::Energy => SENSORS_SUBFEATURE_ENERGY_INPUT,
-                    FeatureType::Current => SENSORS_SUBFEATURE_CURR_INPUT,
-                    FeatureType::Humidity => SENSORS_SUBFEATURE_HUMIDITY_INPUT,
-                    _ => return None
-                }
-            },
-            Self::Min => {
-                match feature_type {
-                    FeatureType::In => SENSORS_SUBFEATURE_IN_MIN,
-                    FeatureType::Fan => SENSORS_SUBFEATURE_FAN_MIN,
-                    FeatureType::Temp => SENSORS_SUBFEATURE_TEMP_MIN,
-                    FeatureType::Power => SENSORS_SUBFEATURE_POWER_MIN,
-                    FeatureType::Current => SENSORS_SUBFEATURE_CURR_MIN,
-                    _ => return None
-                }
-            },
-            Self::Max => {
-                match feature_type {
-                    FeatureType::In => SENSORS_SUBFEATURE_IN_MAX,
-                    FeatureType::Fan => SENSORS_SUBFEATURE_FAN_MAX,
-                    FeatureType::Temp => SENSORS_SUBFEATURE_TEMP_MAX,
-                    FeatureType::Power => SENSORS_SUBFEATURE_POWER_MAX,
-                    FeatureType::Current => SENSORS_SUBFEATURE_CURR_MAX,
-                    _ => return None
-                }
-            },
-        })
-    }
-}
\ No newline at end of file
+        self.to_subfeature_type(feature_type)
+            .map(|t| t as ffi::sensors_subfeature_type::Type)
+    }
+}
+impl SubfeatureType {
+    /// The feature type and generic role of this subfeature type,
+    /// or None if it has no generic equivalent (e.g. [`SubfeatureType::FanDiv`]).
+    pub fn to_generic(self) -> Option<(FeatureType, GenericSubfeature)> {
+        GENERIC_SUBFEATURES.iter()
+            .find(|(_, _, subfeature)| *subfeature == self)
+            .map(|(generic, feature, _)| (*feature, *generic))
+    }
+}
+
+/// Every concrete subfeature type with a generic role.
+const GENERIC_SUBFEATURES: &[(GenericSubfeature, FeatureType, SubfeatureType)] = &[
+    (GenericSubfeature::Input, FeatureType::In, SubfeatureType::InInput),
+    (GenericSubfeature::Input, FeatureType::Fan, SubfeatureType::FanInput),
+    (GenericSubfeature::Input, FeatureType::Temp, SubfeatureType::TempInput),
+    (GenericSubfeature::Input, FeatureType::Power, SubfeatureType::PowerInput),
+    (GenericSubfeature::Input, FeatureType::Energy, SubfeatureType::EnergyInput),
+    (GenericSubfeature::Input, FeatureType::Current, SubfeatureType::CurrentInput),
+    (GenericSubfeature::Input, FeatureType::Humidity, SubfeatureType::HumidityInput),
+    (GenericSubfeature::Input, FeatureType::Vid, SubfeatureType::Vid),
+    (GenericSubfeature::Input, FeatureType::BeepEnable, SubfeatureType::BeepEnable),
+    (GenericSubfeature::Min, FeatureType::In, SubfeatureType::InMin),
+    (GenericSubfeature::Min, FeatureType::Fan, SubfeatureType::FanMin),
+    (GenericSubfeature::Min, FeatureType::Temp, SubfeatureType::TempMin),
+    (GenericSubfeature::Min, FeatureType::Power, SubfeatureType::PowerMin),
+    (GenericSubfeature::Min, FeatureType::Current, SubfeatureType::CurrentMin),
+    (GenericSubfeature::Max, FeatureType::In, SubfeatureType::InMax),
+    (GenericSubfeature::Max, FeatureType::Fan, SubfeatureType::FanMax),
+    (GenericSubfeature::Max, FeatureType::Temp, SubfeatureType::TempMax),
+    (GenericSubfeature::Max, FeatureType::Power, SubfeatureType::PowerMax),
+    (GenericSubfeature::Max, FeatureType::Current, SubfeatureType::CurrentMax),
+    (GenericSubfeature::LCrit, FeatureType::In, SubfeatureType::InLCrit),
+    (GenericSubfeature::LCrit, FeatureType::Temp, SubfeatureType::TempLCrit),
+    (GenericSubfeature::LCrit, FeatureType::Power, SubfeatureType::PowerLCrit),
+    (GenericSubfeature::LCrit, FeatureType::Current, SubfeatureType::CurrentLCrit),
+    (GenericSubfeature::Crit, FeatureType::In, SubfeatureType::InCrit),
+    (GenericSubfeature::Crit, FeatureType::Temp, SubfeatureType::TempCrit),
+    (GenericSubfeature::Crit, FeatureType::Power, SubfeatureType::PowerCrit),
+    (GenericSubfeature::Crit, FeatureType::Current, SubfeatureType::CurrentCrit),
+    (GenericSubfeature::Emergency, FeatureType::Temp, SubfeatureType::TempEmergency),
+    (GenericSubfeature::Average, FeatureType::In, SubfeatureType::InAverage),
+    (GenericSubfeature::Average, FeatureType::Power, SubfeatureType::PowerAverage),
+    (GenericSubfeature::Average, FeatureType::Current, SubfeatureType::CurrentAverage),
+    (GenericSubfeature::Lowest, FeatureType::In, SubfeatureType::InLowest),
+    (GenericSubfeature::Lowest, FeatureType::Temp, SubfeatureType::TempLowest),
+    (GenericSubfeature::Lowest, FeatureType::Power, SubfeatureType::PowerInputLowest),
+    (GenericSubfeature::Lowest, FeatureType::Current, SubfeatureType::CurrentLowest),
+    (GenericSubfeature::Highest, FeatureType::In, SubfeatureType::InHighest),
+    (GenericSubfeature::Highest, FeatureType::Temp, SubfeatureType::TempHighest),
+    (GenericSubfeature::Highest, FeatureType::Power, SubfeatureType::PowerInputHighest),
+    (GenericSubfeature::Highest, FeatureType::Current, SubfeatureType::CurrentHighest),
+    (GenericSubfeature::Alarm, FeatureType::In, SubfeatureType::InAlarm),
+    (GenericSubfeature::Alarm, FeatureType::Fan, SubfeatureType::FanAlarm),
+    (GenericSubfeature::Alarm, FeatureType::Temp, SubfeatureType::TempAlarm),
+    (GenericSubfeature::Alarm, FeatureType::Power, SubfeatureType::PowerAlarm),
+    (GenericSubfeature::Alarm, FeatureType::Current, SubfeatureType::CurrentAlarm),
+    (GenericSubfeature::Alarm, FeatureType::Intrusion, SubfeatureType::IntrusionAlarm),
+    (GenericSubfeature::MinAlarm, FeatureType::In, SubfeatureType::InMinAlarm),
+    (GenericSubfeature::MinAlarm, FeatureType::Fan, SubfeatureType::FanMinAlarm),
+    (GenericSubfeature::MinAlarm, FeatureType::Temp, SubfeatureType::TempMinAlarm),
+    (GenericSubfeature::MinAlarm, FeatureType::Power, SubfeatureType::PowerMinAlarm),
+    (GenericSubfeature::MinAlarm, FeatureType::Current, SubfeatureType::CurrentMinAlarm),
+    (GenericSubfeature::MaxAlarm, FeatureType::In, SubfeatureType::InMaxAlarm),
+    (GenericSubfeature::MaxAlarm, FeatureType::Fan, SubfeatureType::FanMaxAlarm),
+    (GenericSubfeature::MaxAlarm, FeatureType::Temp, SubfeatureType::TempMaxAlarm),
+    (GenericSubfeature::MaxAlarm, FeatureType::Power, SubfeatureType::PowerMaxAlarm),
+    (GenericSubfeature::MaxAlarm, FeatureType::Current, SubfeatureType::CurrentMaxAlarm),
+    (GenericSubfeature::CritAlarm, FeatureType::In, SubfeatureType::InCritAlarm),
+    (GenericSubfeature::CritAlarm, FeatureType::Temp, SubfeatureType::TempCritAlarm),
+    (GenericSubfeature::CritAlarm, FeatureType::Power, SubfeatureType::PowerCritAlarm),
+    (GenericSubfeature::CritAlarm, FeatureType::Current, SubfeatureType::CurrentCritAlarm),
+    (GenericSubfeature::Fault, FeatureType::Fan, SubfeatureType::FanFault),
+    (GenericSubfeature::Fault, FeatureType::Temp, SubfeatureType::TempFault),
+    (GenericSubfeature::Beep, FeatureType::In, SubfeatureType::InBeep),
+    (GenericSubfeature::Beep, FeatureType::Fan, SubfeatureType::FanBeep),
+    (GenericSubfeature::Beep, FeatureType::Temp, SubfeatureType::TempBeep),
+    (GenericSubfeature::Beep, FeatureType::Current, SubfeatureType::CurrentBeep),
+    (GenericSubfeature::Beep, FeatureType::Intrusion, SubfeatureType::IntrusionBeep),
+    (GenericSubfeature::Offset, FeatureType::Temp, SubfeatureType::TempOffset),
+    (GenericSubfeature::Hysteresis, FeatureType::Temp, SubfeatureType::TempMaxHyst),
+    (GenericSubfeature::Hysteresis, FeatureType::Power, SubfeatureType::PowerCapHyst),
+];
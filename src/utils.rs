@@ -1,5 +1,7 @@
 use std::{borrow::Borrow, ffi::{CStr, c_void}, ops::Deref};
 
+use crate::error::Error;
+
 
 #[inline]
 /// This wonderful function transforms an Option<Result<T, E>> into a Result<Option<T>, E>.
@@ -31,6 +33,15 @@ pub unsafe fn ptr_to_ref<'a, T>(ptr: *const T) -> Result<Option<&'a T>, ()> {
     }
 }
 
+/// Like [`ptr_to_ref`], but reports a misaligned pointer as [`Error::InvalidPointer`] describing `what` it should have pointed to.
+/// 
+/// # Safety
+/// The same requirements as for [`ptr_to_ref`] apply.
+pub unsafe fn checked_ptr_to_ref<'a, T>(ptr: *const T, what: &'static str) -> crate::error::Result<Option<&'a T>> {
+    // SAFETY: Guaranteed by the caller.
+    unsafe { ptr_to_ref(ptr) }.map_err(|_| Error::InvalidPointer { what, context: None })
+}
+
 /// Tries to convert a pointer into a borrowed CStr, returning None if the pointer is null.
 /// 
 /// # Safety
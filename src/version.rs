@@ -0,0 +1,101 @@
+use std::{ffi::CStr, fmt::Display, str::FromStr};
+
+use crate::error::Error;
+
+/// A libsensors version, as reported by the `libsensors_version` symbol (e.g. `3.6.0`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+impl Version {
+    /// The major version the bundled bindings were written for.
+    /// Struct layouts may differ in any other major version.
+    pub const SUPPORTED_MAJOR: u32 = 3;
+
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self { major, minor, patch }
+    }
+
+    /// Whether the layouts of this version match the bindings.
+    pub fn is_compatible(&self) -> bool {
+        self.major == Self::SUPPORTED_MAJOR
+    }
+
+    /// Reads the version string exported by libsensors.
+    ///
+    /// # Safety
+    /// `raw` must be null or point to a valid C-string.
+    pub(crate) unsafe fn from_raw(raw: *const std::ffi::c_char) -> Option<Self> {
+        if raw.is_null() {
+            return None
+        }
+        // SAFETY: Guaranteed by the caller.
+        unsafe { CStr::from_ptr(raw) }.to_str().ok()?.parse().ok()
+    }
+}
+impl Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+impl FromStr for Version {
+    type Err = Error;
+
+    /// Parses `major.minor[.patch]`, followed by an optional pre-release (`-rc1`) or build (`+git`) suffix.
+    ///
+    /// The suffix is ignored, so `3.6.0-rc1` is treated as `3.6.0`: a pre-release already has
+    /// the layouts of the release it leads up to, and refusing it would make libsensors unusable.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::parse("version", s);
+        let numbers = s.split(['-', '+']).next().unwrap_or(s);
+        let mut parts = numbers.split('.').map(|part| match part.bytes().all(|b| b.is_ascii_digit()) {
+            true => part.parse::<u32>().ok(),
+            false => None,
+        });
+        let major = parts.next().flatten().ok_or_else(invalid)?;
+        let minor = parts.next().flatten().ok_or_else(invalid)?;
+        let patch = match parts.next() {
+            Some(patch) => patch.ok_or_else(invalid)?,
+            None => 0,
+        };
+        if parts.next().is_some() {
+            return Err(invalid())
+        }
+        Ok(Self { major, minor, patch })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        for (s, version) in [
+            ("3.6.0", Version::new(3, 6, 0)),
+            ("3.5", Version::new(3, 5, 0)),
+            ("10.20.30", Version::new(10, 20, 30)),
+            ("3.6.0-rc1", Version::new(3, 6, 0)),
+            ("3.6.1+git", Version::new(3, 6, 1)),
+        ] {
+            assert_eq!(s.parse::<Version>().unwrap(), version, "{s}");
+        }
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for s in ["", "3", "3.", "3.6.", "3.6.0.1", "3.6x", "3.x.0", "+3.6.0", "-3.6", "3.6.0 "] {
+            assert!(matches!(s.parse::<Version>(), Err(Error::Parse { .. })), "{s}");
+        }
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let version = Version::new(3, 6, 0);
+        assert_eq!(version.to_string(), "3.6.0");
+        assert_eq!(version.to_string().parse::<Version>().unwrap(), version);
+        assert!(Version::new(3, 6, 0) > Version::new(3, 5, 12));
+    }
+}
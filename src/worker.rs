@@ -0,0 +1,209 @@
+use std::{error::Error as StdError, ffi::c_double, fmt::Display, io, result::Result as StdResult, str::FromStr, sync::mpsc::{self, Receiver, Sender}, thread::{self, JoinHandle}};
+
+use log::warn;
+
+use crate::{Chip, ChipName, ChipPattern, ConfigSource, LibSensors, LoadingError, ReloadDiff, Snapshot, Subfeature, error::{Error, ErrorKind, Result, SensorsError}};
+
+/// Selects a subfeature by name on every chip matching a pattern.
+/// 
+/// The string form is `chip/subfeature`, e.g. `coretemp-isa-0000/temp1_input` or `nct6775-*/fan1_input`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SubfeatureSelector {
+    pub chip: ChipPattern,
+    pub subfeature: String,
+}
+impl Display for SubfeatureSelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.chip, self.subfeature)
+    }
+}
+impl FromStr for SubfeatureSelector {
+    type Err = Error;
+
+    fn from_str(s: &str) -> StdResult<Self, Self::Err> {
+        let (chip, subfeature) = s.split_once('/')
+            .ok_or_else(|| Error::parse("subfeature selector", s))?;
+        Ok(Self { chip: chip.parse()?, subfeature: subfeature.to_owned() })
+    }
+}
+
+/// The result of an operation on one chip matched by a [`SubfeatureSelector`].
+#[derive(Debug)]
+pub struct Reading<T> {
+    pub chip: ChipName,
+    pub result: Result<T>,
+}
+
+/// The results of an operation on every chip matched by a [`SubfeatureSelector`].
+#[derive(Debug)]
+pub struct Readings<T> {
+    pub readings: Vec<Reading<T>>,
+    /// Errors from chips that could not be accessed at all, like in [`Snapshot::errors`].
+    pub errors: Vec<Error>,
+}
+
+#[derive(Debug)]
+pub enum WorkerError {
+    /// The worker thread could not be started.
+    Spawn(io::Error),
+    /// The worker thread is no longer running.
+    Disconnected,
+    Sensors(Error),
+    Loading(LoadingError),
+}
+impl Display for WorkerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Spawn(_) => write!(f, "Failed to start sensor worker"),
+            Self::Disconnected => write!(f, "Sensor worker is not running"),
+            Self::Sensors(_) => write!(f, "Sensor worker request failed"),
+            Self::Loading(_) => write!(f, "Sensor worker failed to load libsensors"),
+        }
+    }
+}
+impl StdError for WorkerError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Spawn(e) => Some(e),
+            Self::Disconnected => None,
+            Self::Sensors(e) => Some(e),
+            Self::Loading(e) => Some(e),
+        }
+    }
+}
+impl From<Error> for WorkerError {
+    fn from(value: Error) -> Self {
+        Self::Sensors(value)
+    }
+}
+impl From<LoadingError> for WorkerError {
+    fn from(value: LoadingError) -> Self {
+        Self::Loading(value)
+    }
+}
+
+type Reply<T> = Sender<StdResult<T, WorkerError>>;
+
+enum Request {
+    Snapshot(Reply<Snapshot>),
+    Read(SubfeatureSelector, Reply<Readings<c_double>>),
+    Set(SubfeatureSelector, c_double, Reply<Readings<()>>),
+    Reload(ConfigSource, Reply<ReloadDiff>),
+}
+
+/// Owns the [`LibSensors`] instance on a dedicated thread and answers requests over channels.
+/// 
+/// All results are owned, so this can be used from any number of threads (e.g. async tasks)
+/// without running into the single-instance restriction or the thread-unsafety of libsensors.
+/// The worker thread stops once this is dropped.
+#[derive(Debug)]
+pub struct SensorsWorker {
+    sender: Option<Sender<Request>>,
+    thread: Option<JoinHandle<()>>,
+}
+impl SensorsWorker {
+    /// Starts the worker thread and initialises libsensors on it using `config`.
+    pub fn spawn(config: impl Into<ConfigSource>) -> StdResult<Self, WorkerError> {
+        let config = config.into();
+        let (sender, requests) = mpsc::channel();
+        let (init_sender, init) = mpsc::channel();
+        let thread = thread::Builder::new()
+            .name("libsensors".into())
+            .spawn(move || {
+                match LibSensors::init_with_source(config) {
+                    Ok(lib) => {
+                        // The receiver only goes away if spawn has already returned, which cannot happen before this.
+                        let _ = init_sender.send(Ok(()));
+                        run(lib, requests)
+                    },
+                    Err(e) => { let _ = init_sender.send(Err(e)); }
+                }
+            })
+            .map_err(WorkerError::Spawn)?;
+        init.recv().map_err(|_| WorkerError::Disconnected)??;
+        Ok(Self { sender: Some(sender), thread: Some(thread) })
+    }
+
+    fn request<T>(&self, make: impl FnOnce(Reply<T>) -> Request) -> StdResult<T, WorkerError> {
+        let (reply, response) = mpsc::channel();
+        self.sender.as_ref()
+            .ok_or(WorkerError::Disconnected)?
+            .send(make(reply))
+            .map_err(|_| WorkerError::Disconnected)?;
+        response.recv().map_err(|_| WorkerError::Disconnected)?
+    }
+
+    pub fn snapshot(&self) -> StdResult<Snapshot, WorkerError> {
+        self.request(Request::Snapshot)
+    }
+
+    /// Reads the selected subfeature on every matching chip.
+    pub fn read(&self, selector: SubfeatureSelector) -> StdResult<Readings<c_double>, WorkerError> {
+        self.request(|reply| Request::Read(selector, reply))
+    }
+
+    /// Writes `value` to the selected subfeature on every matching chip.
+    pub fn set(&self, selector: SubfeatureSelector, value: c_double) -> StdResult<Readings<()>, WorkerError> {
+        self.request(|reply| Request::Set(selector, value, reply))
+    }
+
+    /// Reinitialises libsensors with `config`, picking up new devices and configuration changes.
+    /// 
+    /// See [`LibSensors::reload`].
+    pub fn reload(&self, config: impl Into<ConfigSource>) -> StdResult<ReloadDiff, WorkerError> {
+        let config = config.into();
+        self.request(|reply| Request::Reload(config, reply))
+    }
+}
+impl Drop for SensorsWorker {
+    fn drop(&mut self) {
+        // Closing the channel stops the worker loop.
+        drop(self.sender.take());
+        if let Some(thread) = self.thread.take()
+            && thread.join().is_err() {
+            warn!("Sensor worker thread panicked");
+        }
+    }
+}
+
+fn run(mut lib: LibSensors, requests: Receiver<Request>) {
+    for request in requests {
+        // A failed send only means the requester stopped waiting, which is fine.
+        match request {
+            Request::Snapshot(reply) => {
+                let _ = reply.send(Ok(lib.snapshot()));
+            },
+            Request::Read(selector, reply) => {
+                let _ = reply.send(Ok(for_each_selected(&lib, &selector, |s| s.get_value())));
+            },
+            Request::Set(selector, value, reply) => {
+                let _ = reply.send(Ok(for_each_selected(&lib, &selector, |s| s.set_value(value))));
+            },
+            Request::Reload(config, reply) => {
+                let _ = reply.send(lib.reload(config).map_err(Into::into));
+            },
+        }
+    }
+}
+
+fn for_each_selected<T>(
+    lib: &LibSensors,
+    selector: &SubfeatureSelector,
+    mut f: impl FnMut(&Subfeature<'_>) -> Result<T>
+) -> Readings<T> {
+    let mut readings = Readings { readings: Vec::new(), errors: Vec::new() };
+    for chip in lib.get_chips_matching(&selector.chip) {
+        match chip {
+            Ok(chip) => readings.readings.push(Reading { chip: chip.name(), result: apply(&chip, &selector.subfeature, &mut f) }),
+            Err(e) => readings.errors.push(e),
+        }
+    }
+    readings
+}
+
+fn apply<T>(chip: &Chip<'_>, name: &str, f: impl FnOnce(&Subfeature<'_>) -> Result<T>) -> Result<T> {
+    match chip.get_subfeature_by_name(name)? {
+        Some(subfeature) => f(&subfeature),
+        None => Err(Error::from(SensorsError::from(ErrorKind::NoEntry)).with_context(chip.error_context())),
+    }
+}